@@ -6,14 +6,38 @@
 //! # Examples
 //!
 //! ```
-//! use augusto::ascii_art::word_art;
+//! use augusto::ascii_art::{word_art_styled, ArtStyle};
 //!
-//! let result = word_art("HI", "rust");
-//! println!("{}", result);
+//! let result = word_art_styled("HI", "rust", &ArtStyle::default());
+//! println!("{}", result.to_text());
 //! ```
 
 use std::collections::HashMap;
 
+use canvas::{Canvas, Cell, Layer, Style};
+use effects::{Effect, Glyph};
+use fill::{FillStrategy, Mask};
+use font::Font;
+use layout::{Layout, Transform};
+use scale::ScaleMode;
+
+pub mod animation;
+pub mod bitmap_font;
+pub mod calligram;
+pub mod canvas;
+pub mod color;
+pub mod editor;
+pub mod effects;
+pub mod fill;
+pub mod font;
+pub mod html;
+pub mod layout;
+pub mod raster;
+pub mod recognize;
+pub mod scale;
+pub mod shape;
+pub mod svg;
+
 /// ASCII art letter patterns using a 5x5 grid
 /// Each letter is represented as a vector of strings
 fn get_letter_pattern(letter: char) -> Vec<String> {
@@ -92,6 +116,7 @@ fn get_letter_pattern(letter: char) -> Vec<String> {
 /// - Uses the filler word cyclically if the pattern needs more characters
 /// - Each letter is displayed using a 5x5 character grid
 /// - Letters are separated by one space
+#[cfg(test)]
 pub fn word_art(main_word: &str, filler_word: &str) -> String {
     word_art_with_spacing(main_word, filler_word, 1)
 }
@@ -109,44 +134,298 @@ pub fn word_art(main_word: &str, filler_word: &str) -> String {
 /// # Returns
 ///
 /// A `String` containing the ASCII art representation
+#[cfg(test)]
 pub fn word_art_with_spacing(main_word: &str, filler_word: &str, spacing: usize) -> String {
-    if main_word.is_empty() || filler_word.is_empty() {
-        return String::new();
+    word_art_canvas(main_word, filler_word, spacing).to_text()
+}
+
+/// Escape a string for use in XML/HTML text or attribute values
+fn escape_markup(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            _ => output.push(ch),
+        }
     }
+    output
+}
 
-    let main_chars: Vec<char> = main_word.chars().collect();
-    let filler_chars: Vec<char> = filler_word.chars().collect();
-    let mut filler_index = 0;
+/// Options controlling how a word art piece is built
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtStyle {
+    /// Number of blank columns between letters
+    pub spacing: usize,
+    /// How filler characters are distributed over the strokes
+    pub strategy: FillStrategy,
+    /// Glyph effects, applied in order after scaling
+    pub effects: Vec<Effect>,
+    /// Filler used for drop-shadow cells
+    pub shadow_filler: String,
+    /// Glyph scale factor; 1 keeps the 5x5 grid
+    pub scale: f64,
+    /// How glyphs are resampled when scaled
+    pub scale_mode: ScaleMode,
+    /// Where letters are placed relative to each other
+    pub layout: Layout,
+    /// Mirrors and rotations applied to the composed grid, in order
+    pub transforms: Vec<Transform>,
+    /// Glyphs the letters are drawn with
+    pub font: Font,
+    /// Use the font's proportional widths and kerning instead of full glyphs
+    pub proportional: bool,
+}
 
-    let patterns: Vec<Vec<String>> = main_chars.iter().map(|&c| get_letter_pattern(c)).collect();
+impl Default for ArtStyle {
+    fn default() -> Self {
+        Self {
+            spacing: 1,
+            strategy: FillStrategy::Global,
+            effects: Vec::new(),
+            shadow_filler: ".".to_string(),
+            scale: 1.0,
+            scale_mode: ScaleMode::Nearest,
+            layout: Layout::Horizontal,
+            transforms: Vec::new(),
+            font: Font::builtin(),
+            proportional: false,
+        }
+    }
+}
 
-    let mut output = String::new();
+/// Renders a word art piece into a [`Canvas`]
+///
+/// This is the intermediate representation behind [`word_art_with_spacing`];
+/// renderers that need more than plain text (colours, vector output) start here.
+///
+/// # Arguments
+///
+/// * `main_word` - The word to display in ASCII art
+/// * `filler_word` - The word to use as filler characters
+/// * `spacing` - Number of blank columns between letters
+///
+/// # Returns
+///
+/// A `Canvas`, empty if either word is empty
+// The commands build their art through `word_art_styled`, so these
+// default-style shortcuts only serve the tests
+#[cfg(test)]
+pub fn word_art_canvas(main_word: &str, filler_word: &str, spacing: usize) -> Canvas {
+    let style = ArtStyle {
+        spacing,
+        ..ArtStyle::default()
+    };
+    word_art_styled(main_word, filler_word, &style)
+}
 
-    for line_num in 0..5 {
-        let mut line = String::new();
+/// Renders a word art piece into a [`Canvas`] with full styling control
+///
+/// # Arguments
+///
+/// * `main_word` - The word to display in ASCII art
+/// * `filler_word` - The word to use as filler characters; with
+///   [`FillStrategy::WordPerLetter`] a comma-separated list of words
+/// * `style` - Spacing, fill strategy, scale, layout and other options
+///
+/// # Returns
+///
+/// A `Canvas`, empty if either word is empty
+pub fn word_art_styled(main_word: &str, filler_word: &str, style: &ArtStyle) -> Canvas {
+    let fillers = style.strategy.filler_words(filler_word);
+    if main_word.is_empty() || fillers.is_empty() {
+        return Canvas::new(0, 0);
+    }
+
+    let letters: Vec<char> = main_word.chars().collect();
+    let glyphs: Vec<Glyph> = letters
+        .iter()
+        .map(|&c| {
+            let mask = if style.proportional {
+                style.font.proportional_glyph(c)
+            } else {
+                style.font.glyph(c)
+            };
+            let scaled = scale::scale_mask(&mask, style.scale, style.scale_mode);
+            style
+                .effects
+                .iter()
+                .fold(Glyph::new(scaled), |glyph, &effect| {
+                    effects::apply(&glyph, effect)
+                })
+        })
+        .collect();
+
+    let strokes: Vec<Mask> = glyphs.iter().map(|g| g.stroke.clone()).collect();
+    let lengths: Vec<usize> = fillers.iter().map(Vec::len).collect();
+    let positions = fill::assign(&strokes, &lengths, style.strategy);
+
+    let shadow_filler: Vec<char> = style.shadow_filler.chars().collect();
+    let shadows: Vec<Mask> = glyphs.iter().map(|g| g.shadow.clone()).collect();
+    let shadow_positions = fill::assign(&shadows, &[shadow_filler.len()], FillStrategy::Global);
+
+    let sizes: Vec<(usize, usize)> = glyphs.iter().map(Glyph::size).collect();
+    let kerning: Vec<isize> = if style.proportional {
+        letters
+            .windows(2)
+            .map(|pair| {
+                // Font pairs are left to right, so a right-to-left word kerns them swapped
+                let (left, right) = match style.layout {
+                    Layout::RightToLeft => (pair[1], pair[0]),
+                    _ => (pair[0], pair[1]),
+                };
+                (style.font.kerning(left, right) as f64 * style.scale).round() as isize
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let offsets = layout::letter_offsets(&sizes, style.layout, style.spacing, &kerning);
+
+    let height = sizes
+        .iter()
+        .zip(&offsets)
+        .map(|(&(h, _), &(row, _))| row + h)
+        .max()
+        .unwrap_or(0);
+    let width = sizes
+        .iter()
+        .zip(&offsets)
+        .map(|(&(_, w), &(_, col))| col + w)
+        .max()
+        .unwrap_or(0);
 
-        for (letter_idx, pattern) in patterns.iter().enumerate() {
-            let pattern_line = &pattern[line_num];
+    let mut canvas = Canvas::new(width, height);
 
-            for ch in pattern_line.chars() {
-                if ch == '#' {
-                    line.push(filler_chars[filler_index % filler_chars.len()]);
-                    filler_index += 1;
-                } else {
-                    line.push(' ');
+    // Shadows go down first so that strokes of neighbouring letters cover them
+    let layers = [
+        (&shadow_positions, Layer::Shadow),
+        (&positions, Layer::Stroke),
+    ];
+    for (layer_positions, layer) in layers {
+        for (letter_idx, letter) in layer_positions.iter().enumerate() {
+            let filler = match layer {
+                Layer::Shadow => &shadow_filler,
+                Layer::Stroke => &fillers[letter_idx % fillers.len()],
+            };
+            let (glyph_height, glyph_width) = sizes[letter_idx];
+            let mut glyph_layer = Canvas::new(glyph_width, glyph_height);
+
+            for (r, line) in letter.iter().enumerate() {
+                for (c, position) in line.iter().enumerate() {
+                    if let Some(position) = *position {
+                        glyph_layer.set(
+                            r,
+                            c,
+                            Cell {
+                                ch: filler[position],
+                                style: Style::default(),
+                                letter: letters[letter_idx],
+                                letter_index: letter_idx,
+                                filler_index: position,
+                                layer,
+                            },
+                        );
+                    }
                 }
             }
 
-            if letter_idx < patterns.len() - 1 {
-                line.push_str(&" ".repeat(spacing));
-            }
+            let (top, left) = offsets[letter_idx];
+            canvas.overlay(&glyph_layer, top, left);
         }
+    }
 
-        output.push_str(&line);
-        output.push('\n');
+    for &transform in &style.transforms {
+        canvas = layout::transform(&canvas, transform);
     }
 
-    output
+    canvas
+}
+
+/// Fills an arbitrary mask with a word, the way glyph strokes are filled
+///
+/// Used for shapes that do not come from the letter table, such as images.
+/// Cells carry no source letter (`letter` is a space) and the whole mask
+/// counts as letter 0.
+///
+/// # Arguments
+///
+/// * `mask` - Cells to fill, `true` where a filler character goes
+/// * `filler_word` - The word to use as filler characters
+/// * `strategy` - How the filler is distributed over the mask
+///
+/// # Returns
+///
+/// A `Canvas` the size of the mask, empty if the filler is empty
+pub fn fill_mask(mask: &Mask, filler_word: &str, strategy: FillStrategy) -> Canvas {
+    let filler = match strategy.filler_words(filler_word).into_iter().next() {
+        Some(filler) => filler,
+        None => return Canvas::new(0, 0),
+    };
+
+    let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+    let positions = fill::assign(std::slice::from_ref(mask), &[filler.len()], strategy);
+    let cells = positions[0].iter().enumerate().flat_map(|(r, line)| {
+        line.iter()
+            .enumerate()
+            .filter_map(move |(c, position)| position.map(|p| (r, c, p)))
+    });
+
+    shape_canvas(cells, width, mask.len(), &filler)
+}
+
+/// Fills a path with a word, one filler character per cell in path order
+///
+/// The filler reads along the path, so text follows curves such as the
+/// [`calligram`] spiral. Cells are `(row, col)` pairs.
+///
+/// # Returns
+///
+/// A `Canvas` just large enough for the path, empty if the filler is empty
+pub fn fill_path(path: &[(usize, usize)], filler_word: &str) -> Canvas {
+    let filler: Vec<char> = filler_word.chars().collect();
+    if filler.is_empty() {
+        return Canvas::new(0, 0);
+    }
+
+    let height = path.iter().map(|&(r, _)| r + 1).max().unwrap_or(0);
+    let width = path.iter().map(|&(_, c)| c + 1).max().unwrap_or(0);
+    let cells = path
+        .iter()
+        .enumerate()
+        .map(|(i, &(r, c))| (r, c, i % filler.len()));
+
+    shape_canvas(cells, width, height, &filler)
+}
+
+/// Canvas with the given `(row, col, filler position)` cells filled
+fn shape_canvas(
+    cells: impl Iterator<Item = (usize, usize, usize)>,
+    width: usize,
+    height: usize,
+    filler: &[char],
+) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+
+    for (r, c, position) in cells {
+        canvas.set(
+            r,
+            c,
+            Cell {
+                ch: filler[position],
+                style: Style::default(),
+                letter: ' ',
+                letter_index: 0,
+                filler_index: position,
+                layer: Layer::Stroke,
+            },
+        );
+    }
+
+    canvas
 }
 
 #[cfg(test)]
@@ -224,4 +503,183 @@ mod tests {
             assert!(!line.trim().is_empty());
         }
     }
+
+    #[test]
+    fn test_word_art_canvas_provenance() {
+        let canvas = word_art_canvas("HI", "ab", 1);
+
+        assert_eq!(canvas.height(), 5);
+        assert_eq!(canvas.width(), 11);
+
+        // Top-left of H is the first filler character of the first letter
+        let first = canvas.get(0, 0).unwrap();
+        assert_eq!(first.ch, 'a');
+        assert_eq!(first.letter, 'H');
+        assert_eq!(first.letter_index, 0);
+        assert_eq!(first.filler_index, 0);
+        assert_eq!(first.layer, Layer::Stroke);
+
+        // The spacing column between letters is blank
+        assert!((0..5).all(|row| canvas.get(row, 5).is_none()));
+
+        // Cells after the gap belong to the second letter
+        let second = canvas.get(0, 6).unwrap();
+        assert_eq!(second.letter, 'I');
+        assert_eq!(second.letter_index, 1);
+    }
+
+    #[test]
+    fn test_word_art_styled_word_per_letter() {
+        let style = ArtStyle {
+            strategy: FillStrategy::WordPerLetter,
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("II", "ab,xy", &style);
+        let text = canvas.to_text();
+        let first_line = text.lines().next().unwrap();
+
+        // Each letter starts its own filler word
+        assert_eq!(first_line, "ababa xyxyx");
+    }
+
+    #[test]
+    fn test_word_art_styled_inverse() {
+        let style = ArtStyle {
+            effects: vec![Effect::Inverse],
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("I", "o", &style);
+        let lines: Vec<String> = canvas.to_text().lines().map(String::from).collect();
+
+        assert_eq!(lines[0], "     ");
+        assert_eq!(lines[1], "oo oo");
+    }
+
+    #[test]
+    fn test_word_art_styled_scale() {
+        let style = ArtStyle {
+            scale: 3.0,
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("RUST", "code", &style);
+
+        assert_eq!(canvas.height(), 15);
+        assert_eq!(canvas.width(), 4 * 15 + 3);
+        // Strokes are still drawn with the filler word
+        let text = canvas.to_text();
+        assert!(text.chars().all(|c| "code \n".contains(c)));
+    }
+
+    #[test]
+    fn test_word_art_styled_layouts() {
+        let vertical = ArtStyle {
+            layout: Layout::Vertical,
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("HI", "x", &vertical);
+        assert_eq!(canvas.height(), 11);
+        assert_eq!(canvas.width(), 5);
+
+        let rtl = ArtStyle {
+            layout: Layout::RightToLeft,
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("LI", "x", &rtl);
+        assert_eq!(canvas.to_text(), word_art("IL", "x"));
+
+        let rotated = ArtStyle {
+            transforms: vec![Transform::Rotate90],
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("HI", "x", &rotated);
+        assert_eq!(canvas.height(), 11);
+        assert_eq!(canvas.width(), 5);
+    }
+
+    #[test]
+    fn test_word_art_styled_shadow() {
+        let style = ArtStyle {
+            effects: vec![Effect::Shadow { dx: 1, dy: 1 }],
+            shadow_filler: "~".to_string(),
+            ..ArtStyle::default()
+        };
+        let canvas = word_art_styled("I", "o", &style);
+        let lines: Vec<String> = canvas.to_text().lines().map(String::from).collect();
+
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "ooooo ");
+        assert_eq!(lines[1], " ~o~~~");
+        assert_eq!(lines[5], " ~~~~~");
+        assert_eq!(canvas.get(5, 1).unwrap().layer, Layer::Shadow);
+        assert_eq!(canvas.get(0, 0).unwrap().layer, Layer::Stroke);
+    }
+
+    #[test]
+    fn test_word_art_styled_proportional() {
+        let style = ArtStyle {
+            proportional: true,
+            ..ArtStyle::default()
+        };
+
+        // I is three columns wide; L and T are kerned together
+        assert_eq!(
+            word_art_styled("LIT", "x", &style).width(),
+            5 + 1 + 3 + 1 + 5
+        );
+        let lt = word_art_styled("LT", "x", &style).to_text();
+        assert_eq!(lt.lines().next(), Some("x    xxxxx"));
+        assert_eq!(lt.lines().last(), Some("xxxxx  x  "));
+
+        // Right to left, L ends up after T, and T-L is not kerned
+        let rtl = ArtStyle {
+            layout: Layout::RightToLeft,
+            ..style.clone()
+        };
+        let tl = word_art_styled("LT", "x", &rtl).to_text();
+        assert_eq!(tl.lines().next(), Some("xxxxx x    "));
+        assert_eq!(tl.lines().last(), Some("  x   xxxxx"));
+        assert_eq!(
+            word_art_styled("AF", "x", &rtl).width() + 1,
+            word_art_styled("FA", "x", &rtl).width()
+        );
+
+        // Kerning scales with the glyphs
+        let scaled = ArtStyle {
+            scale: 2.0,
+            ..style
+        };
+        assert_eq!(word_art_styled("LT", "x", &scaled).width(), 10 + 1 - 2 + 10);
+        assert_eq!(
+            word_art_styled("LIT", "x", &ArtStyle::default()).width(),
+            17
+        );
+    }
+
+    #[test]
+    fn test_word_art_canvas_matches_text() {
+        let canvas = word_art_canvas("LUXO", "LIXO", 1);
+        assert_eq!(canvas.to_text(), word_art("LUXO", "LIXO"));
+    }
+
+    #[test]
+    fn test_fill_mask_cycles_filler() {
+        let mask = vec![vec![true, false, true], vec![true, true, false]];
+        let canvas = fill_mask(&mask, "abc", FillStrategy::Global);
+
+        assert_eq!(canvas.to_text(), "a b\nca \n");
+        assert_eq!(canvas.get(1, 1).unwrap().filler_index, 0);
+        assert!(fill_mask(&mask, "", FillStrategy::Global)
+            .to_text()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fill_path_follows_path_order() {
+        let path = [(1, 0), (0, 0), (0, 1), (1, 1), (1, 2)];
+        let canvas = fill_path(&path, "abc");
+
+        assert_eq!(canvas.to_text(), "bc \naab\n");
+        assert_eq!(canvas.get(1, 2).unwrap().filler_index, 1);
+        assert!(fill_path(&path, "").to_text().is_empty());
+    }
 }
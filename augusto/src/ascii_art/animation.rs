@@ -0,0 +1,298 @@
+//! Animated word art
+//!
+//! An animation is a list of [`Canvas`] frames of equal size. Three kinds
+//! are built from a word art piece:
+//!
+//! - [`Animation::Reveal`] draws the letters one after another, each along
+//!   its strokes like a pen would
+//! - [`Animation::Scroll`] shifts the filler word through the glyphs
+//! - [`Animation::Morph`] dissolves the piece into the one with main and
+//!   filler words swapped (LUXO/LIXO) and back
+//!
+//! [`play`] shows the frames in the terminal using termion's raw mode, so a
+//! key press (including Ctrl-C) stops playback and the terminal is always
+//! restored.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{ArtStyle, animation::{frames, play, Animation}};
+//!
+//! let frames = frames("LUXO", "LIXO", &ArtStyle::default(), Animation::Morph);
+//! play(&frames, 12.0, 1).unwrap();
+//! ```
+
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use termion::raw::IntoRawMode;
+use termion::{clear, cursor};
+
+use super::canvas::{Canvas, Layer};
+use super::color::render_ansi;
+use super::{fill, word_art_styled, ArtStyle};
+
+/// Upper bound on the number of frames of a reveal
+const REVEAL_FRAMES: usize = 60;
+
+/// Number of frames for one direction of a morph
+const MORPH_FRAMES: usize = 24;
+
+/// Kinds of animation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Animation {
+    /// Letters appear one at a time, stroke by stroke
+    Reveal,
+    /// The filler word scrolls through the glyphs
+    Scroll,
+    /// Main and filler words swap places and back
+    Morph,
+}
+
+impl Animation {
+    /// Parse an animation from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "reveal" | "stroke" => Ok(Animation::Reveal),
+            "scroll" => Ok(Animation::Scroll),
+            "morph" | "swap" => Ok(Animation::Morph),
+            _ => Err(format!(
+                "Unknown animation '{}'. Expected reveal, scroll or morph.",
+                name
+            )),
+        }
+    }
+}
+
+/// Build the frames of an animation
+///
+/// Every frame has the same size; an empty list is returned when either
+/// word is empty.
+pub fn frames(
+    main_word: &str,
+    filler_word: &str,
+    style: &ArtStyle,
+    animation: Animation,
+) -> Vec<Canvas> {
+    let canvas = word_art_styled(main_word, filler_word, style);
+    if canvas.width() == 0 {
+        return Vec::new();
+    }
+
+    match animation {
+        Animation::Reveal => reveal(&canvas),
+        Animation::Scroll => scroll(&canvas, &style.strategy.filler_words(filler_word)),
+        Animation::Morph => {
+            let swapped = word_art_styled(filler_word, main_word, style);
+            morph(&canvas, &swapped)
+        }
+    }
+}
+
+/// Frames drawing each letter along its strokes
+fn reveal(canvas: &Canvas) -> Vec<Canvas> {
+    let letters = canvas
+        .rows()
+        .flatten()
+        .flatten()
+        .map(|cell| cell.letter_index + 1)
+        .max()
+        .unwrap_or(0);
+
+    // Pen order within each letter, over the letter's cells on the canvas
+    let mut order = Vec::new();
+    for letter in 0..letters {
+        let mask: fill::Mask = canvas
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.is_some_and(|c| c.letter_index == letter))
+                    .collect()
+            })
+            .collect();
+        order.extend(fill::stroke_order(&mask));
+    }
+
+    let per_frame = order.len().div_ceil(REVEAL_FRAMES).max(1);
+    let mut frame = Canvas::new(canvas.width(), canvas.height());
+    let mut frames = Vec::new();
+
+    for chunk in order.chunks(per_frame) {
+        for &(row, col) in chunk {
+            if let Some(cell) = canvas.get(row, col) {
+                frame.set(row, col, *cell);
+            }
+        }
+        frames.push(frame.clone());
+    }
+
+    frames
+}
+
+/// Frames shifting every stroke cell one filler character further
+fn scroll(canvas: &Canvas, fillers: &[Vec<char>]) -> Vec<Canvas> {
+    let steps = fillers.iter().map(Vec::len).max().unwrap_or(1).max(1);
+
+    (0..steps)
+        .map(|step| {
+            let mut frame = canvas.clone();
+            for (_, _, cell) in frame.cells_mut() {
+                if cell.layer == Layer::Stroke {
+                    let filler = &fillers[cell.letter_index % fillers.len()];
+                    cell.ch = filler[(cell.filler_index + step) % filler.len()];
+                }
+            }
+            frame
+        })
+        .collect()
+}
+
+/// Frames dissolving `from` into `to` and back again
+fn morph(from: &Canvas, to: &Canvas) -> Vec<Canvas> {
+    let width = from.width().max(to.width());
+    let height = from.height().max(to.height());
+    let from = pad(from, width, height);
+    let to = pad(to, width, height);
+
+    // A fixed scattered order, so the dissolve looks the same every run
+    let mut order: Vec<(usize, usize)> = (0..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .collect();
+    order.sort_by_key(|&(row, col)| ((row * 7 + col * 13) % 23, row, col));
+
+    let mut forward = Vec::with_capacity(MORPH_FRAMES + 1);
+    for step in 0..=MORPH_FRAMES {
+        let switched = order.len() * step / MORPH_FRAMES;
+        let mut frame = from.clone();
+        for &(row, col) in &order[..switched] {
+            frame.clear(row, col);
+            if let Some(cell) = to.get(row, col) {
+                frame.set(row, col, *cell);
+            }
+        }
+        forward.push(frame);
+    }
+
+    let back: Vec<Canvas> = forward[1..forward.len() - 1]
+        .iter()
+        .rev()
+        .cloned()
+        .collect();
+    forward.extend(back);
+    forward
+}
+
+/// Copy of `canvas` enlarged to `width` by `height`
+fn pad(canvas: &Canvas, width: usize, height: usize) -> Canvas {
+    let mut padded = Canvas::new(width, height);
+    padded.overlay(canvas, 0, 0);
+    padded
+}
+
+/// Play frames in the terminal
+///
+/// Frames are drawn in place at `fps` frames per second, `loops` times
+/// (`0` loops forever). Any key stops playback; the terminal leaves raw
+/// mode and shows the cursor again however playback ends, and the last
+/// frame drawn stays on screen.
+pub fn play(frames: &[Canvas], fps: f64, loops: usize) -> io::Result<()> {
+    if !termion::is_tty(&io::stdout()) {
+        return Err(io::Error::other("animation needs a terminal"));
+    }
+
+    let delay = Duration::from_secs_f64(1.0 / fps);
+    let height = frames.first().map_or(0, Canvas::height);
+    let mut keys = termion::async_stdin();
+    // Dropping these restores the cursor and the terminal mode
+    let mut stdout = cursor::HideCursor::from(io::stdout().into_raw_mode()?);
+
+    let mut first = true;
+    let mut round = 0;
+    'playback: while loops == 0 || round < loops {
+        for frame in frames {
+            let started = Instant::now();
+
+            if !first && height > 0 {
+                write!(stdout, "\r{}", cursor::Up(height as u16))?;
+            }
+            first = false;
+
+            // Raw mode needs explicit carriage returns
+            for line in render_ansi(frame).lines() {
+                write!(stdout, "{}{}\r\n", line, clear::UntilNewline)?;
+            }
+            stdout.flush()?;
+
+            let mut pressed = [0u8; 16];
+            if keys.read(&mut pressed)? > 0 {
+                break 'playback;
+            }
+            thread::sleep(delay.saturating_sub(started.elapsed()));
+        }
+        round += 1;
+    }
+
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_animation() {
+        assert_eq!(Animation::parse("Reveal"), Ok(Animation::Reveal));
+        assert_eq!(Animation::parse("scroll"), Ok(Animation::Scroll));
+        assert_eq!(Animation::parse("swap"), Ok(Animation::Morph));
+        assert!(Animation::parse("spin").is_err());
+    }
+
+    #[test]
+    fn test_reveal_ends_with_full_art() {
+        let style = ArtStyle::default();
+        let frames = frames("HI", "rust", &style, Animation::Reveal);
+        let full = word_art_styled("HI", "rust", &style);
+
+        assert_eq!(frames.last(), Some(&full));
+        // Letters are drawn in order: H is complete before I starts
+        let filled = |frame: &Canvas| frame.rows().flatten().flatten().count();
+        let counts: Vec<usize> = frames.iter().map(filled).collect();
+        assert!(counts.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(frames[0]
+            .rows()
+            .flatten()
+            .flatten()
+            .all(|c| c.letter == 'H'));
+    }
+
+    #[test]
+    fn test_scroll_shifts_filler() {
+        let frames = frames("I", "abc", &ArtStyle::default(), Animation::Scroll);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].to_text().lines().next(), Some("abcab"));
+        assert_eq!(frames[1].to_text().lines().next(), Some("bcabc"));
+        assert_eq!(frames[2].to_text().lines().next(), Some("cabca"));
+    }
+
+    #[test]
+    fn test_morph_swaps_words_and_returns() {
+        let style = ArtStyle::default();
+        let frames = frames("LUXO", "LIXO", &style, Animation::Morph);
+
+        assert_eq!(frames.len(), 2 * MORPH_FRAMES);
+        assert_eq!(frames[0], word_art_styled("LUXO", "LIXO", &style));
+        assert_eq!(
+            frames[MORPH_FRAMES],
+            word_art_styled("LIXO", "LUXO", &style)
+        );
+        assert!(frames
+            .iter()
+            .all(|f| f.width() == frames[0].width() && f.height() == frames[0].height()));
+    }
+
+    #[test]
+    fn test_frames_empty_for_empty_words() {
+        assert!(frames("", "x", &ArtStyle::default(), Animation::Reveal).is_empty());
+    }
+}
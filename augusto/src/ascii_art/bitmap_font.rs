@@ -0,0 +1,155 @@
+//! Embedded bitmap font for raster output
+//!
+//! A 5x7 font covering printable ASCII, taken from the public-domain X11
+//! "misc-fixed" 5x7 font, so raster output never depends on system fonts.
+//! Each glyph is seven rows; in every row the five most significant bits are
+//! the pixels from left to right.
+
+/// Width of a glyph in pixels
+pub const GLYPH_WIDTH: usize = 5;
+
+/// Height of a glyph in pixels
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// Glyphs for ASCII 0x20 (space) through 0x7E (tilde)
+const GLYPHS: [[u8; GLYPH_HEIGHT]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x20, 0x20, 0x20, 0x20, 0x00, 0x20, 0x00], // '!'
+    [0x50, 0x50, 0x50, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x00, 0x50, 0xF8, 0x50, 0xF8, 0x50, 0x00], // '#'
+    [0x00, 0x70, 0xA0, 0x70, 0x28, 0x70, 0x00], // '$'
+    [0x80, 0x90, 0x20, 0x40, 0x90, 0x10, 0x00], // '%'
+    [0x00, 0x40, 0xA0, 0x40, 0xA0, 0x50, 0x00], // '&'
+    [0x20, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00], // "'"
+    [0x20, 0x40, 0x40, 0x40, 0x40, 0x20, 0x00], // '('
+    [0x40, 0x20, 0x20, 0x20, 0x20, 0x40, 0x00], // ')'
+    [0x00, 0x50, 0x20, 0x70, 0x20, 0x50, 0x00], // '*'
+    [0x00, 0x20, 0x20, 0xF8, 0x20, 0x20, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x30, 0x20, 0x40], // ','
+    [0x00, 0x00, 0x00, 0xF0, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x60, 0x60, 0x00], // '.'
+    [0x00, 0x10, 0x20, 0x40, 0x80, 0x00, 0x00], // '/'
+    [0x20, 0x50, 0x50, 0x50, 0x50, 0x20, 0x00], // '0'
+    [0x20, 0x60, 0x20, 0x20, 0x20, 0x70, 0x00], // '1'
+    [0x60, 0x90, 0x10, 0x20, 0x40, 0xF0, 0x00], // '2'
+    [0xF0, 0x10, 0x60, 0x10, 0x90, 0x60, 0x00], // '3'
+    [0x20, 0x60, 0xA0, 0xF0, 0x20, 0x20, 0x00], // '4'
+    [0xF0, 0x80, 0xE0, 0x10, 0x90, 0x60, 0x00], // '5'
+    [0x60, 0x80, 0xE0, 0x90, 0x90, 0x60, 0x00], // '6'
+    [0xF0, 0x10, 0x20, 0x20, 0x40, 0x40, 0x00], // '7'
+    [0x60, 0x90, 0x60, 0x90, 0x90, 0x60, 0x00], // '8'
+    [0x60, 0x90, 0x90, 0x70, 0x10, 0x60, 0x00], // '9'
+    [0x00, 0x60, 0x60, 0x00, 0x60, 0x60, 0x00], // ':'
+    [0x00, 0x60, 0x60, 0x00, 0x60, 0x40, 0x80], // ';'
+    [0x00, 0x10, 0x20, 0x40, 0x20, 0x10, 0x00], // '<'
+    [0x00, 0x00, 0xF0, 0x00, 0xF0, 0x00, 0x00], // '='
+    [0x00, 0x40, 0x20, 0x10, 0x20, 0x40, 0x00], // '>'
+    [0x20, 0x50, 0x10, 0x20, 0x00, 0x20, 0x00], // '?'
+    [0x60, 0x90, 0xB0, 0xB0, 0x80, 0x60, 0x00], // '@'
+    [0x60, 0x90, 0x90, 0xF0, 0x90, 0x90, 0x00], // 'A'
+    [0xE0, 0x90, 0xE0, 0x90, 0x90, 0xE0, 0x00], // 'B'
+    [0x60, 0x90, 0x80, 0x80, 0x90, 0x60, 0x00], // 'C'
+    [0xE0, 0x90, 0x90, 0x90, 0x90, 0xE0, 0x00], // 'D'
+    [0xF0, 0x80, 0xE0, 0x80, 0x80, 0xF0, 0x00], // 'E'
+    [0xF0, 0x80, 0xE0, 0x80, 0x80, 0x80, 0x00], // 'F'
+    [0x60, 0x90, 0x80, 0xB0, 0x90, 0x70, 0x00], // 'G'
+    [0x90, 0x90, 0xF0, 0x90, 0x90, 0x90, 0x00], // 'H'
+    [0x70, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00], // 'I'
+    [0x10, 0x10, 0x10, 0x10, 0x90, 0x60, 0x00], // 'J'
+    [0x90, 0xA0, 0xC0, 0xC0, 0xA0, 0x90, 0x00], // 'K'
+    [0x80, 0x80, 0x80, 0x80, 0x80, 0xF0, 0x00], // 'L'
+    [0x90, 0xF0, 0xF0, 0x90, 0x90, 0x90, 0x00], // 'M'
+    [0x90, 0xD0, 0xD0, 0xB0, 0xB0, 0x90, 0x00], // 'N'
+    [0x60, 0x90, 0x90, 0x90, 0x90, 0x60, 0x00], // 'O'
+    [0xE0, 0x90, 0x90, 0xE0, 0x80, 0x80, 0x00], // 'P'
+    [0x60, 0x90, 0x90, 0x90, 0xD0, 0x60, 0x10], // 'Q'
+    [0xE0, 0x90, 0x90, 0xE0, 0xA0, 0x90, 0x00], // 'R'
+    [0x60, 0x90, 0x40, 0x20, 0x90, 0x60, 0x00], // 'S'
+    [0x70, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00], // 'T'
+    [0x90, 0x90, 0x90, 0x90, 0x90, 0x60, 0x00], // 'U'
+    [0x90, 0x90, 0x90, 0x90, 0x60, 0x60, 0x00], // 'V'
+    [0x90, 0x90, 0x90, 0xF0, 0xF0, 0x90, 0x00], // 'W'
+    [0x90, 0x90, 0x60, 0x60, 0x90, 0x90, 0x00], // 'X'
+    [0x50, 0x50, 0x50, 0x20, 0x20, 0x20, 0x00], // 'Y'
+    [0xF0, 0x10, 0x20, 0x40, 0x80, 0xF0, 0x00], // 'Z'
+    [0x70, 0x40, 0x40, 0x40, 0x40, 0x70, 0x00], // '['
+    [0x00, 0x80, 0x40, 0x20, 0x10, 0x00, 0x00], // '\\'
+    [0x70, 0x10, 0x10, 0x10, 0x10, 0x70, 0x00], // ']'
+    [0x20, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0xF0, 0x00], // '_'
+    [0x40, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x70, 0x90, 0xB0, 0x50, 0x00], // 'a'
+    [0x80, 0x80, 0xE0, 0x90, 0x90, 0xE0, 0x00], // 'b'
+    [0x00, 0x00, 0x60, 0x80, 0x80, 0x60, 0x00], // 'c'
+    [0x10, 0x10, 0x70, 0x90, 0x90, 0x70, 0x00], // 'd'
+    [0x00, 0x00, 0x60, 0xB0, 0xC0, 0x60, 0x00], // 'e'
+    [0x20, 0x50, 0x40, 0xE0, 0x40, 0x40, 0x00], // 'f'
+    [0x00, 0x00, 0x70, 0x90, 0x60, 0x80, 0x70], // 'g'
+    [0x80, 0x80, 0xE0, 0x90, 0x90, 0x90, 0x00], // 'h'
+    [0x20, 0x00, 0x60, 0x20, 0x20, 0x70, 0x00], // 'i'
+    [0x10, 0x00, 0x10, 0x10, 0x10, 0x50, 0x20], // 'j'
+    [0x80, 0x80, 0xA0, 0xC0, 0xA0, 0x90, 0x00], // 'k'
+    [0x60, 0x20, 0x20, 0x20, 0x20, 0x70, 0x00], // 'l'
+    [0x00, 0x00, 0xA0, 0xF0, 0x90, 0x90, 0x00], // 'm'
+    [0x00, 0x00, 0xE0, 0x90, 0x90, 0x90, 0x00], // 'n'
+    [0x00, 0x00, 0x60, 0x90, 0x90, 0x60, 0x00], // 'o'
+    [0x00, 0x00, 0xE0, 0x90, 0x90, 0xE0, 0x80], // 'p'
+    [0x00, 0x00, 0x70, 0x90, 0x90, 0x70, 0x10], // 'q'
+    [0x00, 0x00, 0xE0, 0x90, 0x80, 0x80, 0x00], // 'r'
+    [0x00, 0x00, 0x70, 0xC0, 0x30, 0xE0, 0x00], // 's'
+    [0x40, 0x40, 0xE0, 0x40, 0x40, 0x30, 0x00], // 't'
+    [0x00, 0x00, 0x90, 0x90, 0x90, 0x70, 0x00], // 'u'
+    [0x00, 0x00, 0x50, 0x50, 0x50, 0x20, 0x00], // 'v'
+    [0x00, 0x00, 0x90, 0x90, 0xF0, 0xF0, 0x00], // 'w'
+    [0x00, 0x00, 0x90, 0x60, 0x60, 0x90, 0x00], // 'x'
+    [0x00, 0x00, 0x90, 0x90, 0x50, 0x20, 0x40], // 'y'
+    [0x00, 0x00, 0xF0, 0x20, 0x40, 0xF0, 0x00], // 'z'
+    [0x10, 0x20, 0x60, 0x20, 0x20, 0x10, 0x00], // '{'
+    [0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00], // '|'
+    [0x40, 0x20, 0x30, 0x20, 0x20, 0x40, 0x00], // '}'
+    [0x50, 0xA0, 0x00, 0x00, 0x00, 0x00, 0x00], // '~'
+];
+
+/// Dotted box drawn for characters outside printable ASCII
+const FALLBACK: [u8; GLYPH_HEIGHT] = [0x00, 0xA8, 0x00, 0x88, 0x00, 0xA8, 0x00];
+
+/// Look up the bitmap rows for a character
+pub fn glyph(ch: char) -> [u8; GLYPH_HEIGHT] {
+    match ch as u32 {
+        code @ 0x20..=0x7E => GLYPHS[(code - 0x20) as usize],
+        _ => FALLBACK,
+    }
+}
+
+/// Whether the pixel at (`x`, `y`) of a character's glyph is set
+pub fn pixel(ch: char, x: usize, y: usize) -> bool {
+    x < GLYPH_WIDTH && y < GLYPH_HEIGHT && glyph(ch)[y] & (0x80 >> x) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_space_is_blank() {
+        assert_eq!(glyph(' '), [0; GLYPH_HEIGHT]);
+    }
+
+    #[test]
+    fn test_glyph_pixels() {
+        // Top row of 'A' is ".XX.."
+        assert!(!pixel('A', 0, 0));
+        assert!(pixel('A', 1, 0));
+        assert!(pixel('A', 2, 0));
+        assert!(!pixel('A', 3, 0));
+        // Out-of-range coordinates are never set
+        assert!(!pixel('A', GLYPH_WIDTH, 0));
+        assert!(!pixel('A', 0, GLYPH_HEIGHT));
+    }
+
+    #[test]
+    fn test_non_ascii_uses_fallback() {
+        assert_eq!(glyph('é'), FALLBACK);
+        assert_ne!(glyph('~'), FALLBACK);
+    }
+}
@@ -0,0 +1,271 @@
+//! Geometric calligrams
+//!
+//! Procedurally generated shapes for concrete poems that are not made of
+//! letters: a spiral, concentric circle rings, a sine wave, a heart and a
+//! pyramid. Each shape is a *path*, the list of cells in the order the text
+//! should be read: along the curve for the spiral, rings and wave, and row by
+//! row for the filled heart and pyramid.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{fill_path, calligram::{path, CalligramShape}};
+//!
+//! let spiral = path(CalligramShape::Spiral, 8, 2.0);
+//! let canvas = fill_path(&spiral, "concrete poetry ");
+//! println!("{}", canvas.to_text());
+//! ```
+
+use std::collections::HashSet;
+use std::f64::consts::{PI, TAU};
+
+use super::fill::Mask;
+
+/// Rows between two turns of the spiral or two circle rings
+const LINE_GAP: f64 = 2.0;
+
+/// Built-in calligram shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalligramShape {
+    /// Archimedean spiral read from the centre outwards
+    Spiral,
+    /// Concentric rings read clockwise from the top, outermost first
+    Circle,
+    /// Two periods of a sine wave read left to right
+    Wave,
+    /// A filled heart read row by row
+    Heart,
+    /// A filled pyramid read row by row
+    Triangle,
+}
+
+impl CalligramShape {
+    /// Parse a shape from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "spiral" => Ok(CalligramShape::Spiral),
+            "circle" | "rings" => Ok(CalligramShape::Circle),
+            "wave" | "sine" => Ok(CalligramShape::Wave),
+            "heart" => Ok(CalligramShape::Heart),
+            "triangle" | "pyramid" => Ok(CalligramShape::Triangle),
+            _ => Err(format!(
+                "Unknown calligram shape '{}'. Expected spiral, circle, wave, heart or triangle.",
+                name
+            )),
+        }
+    }
+}
+
+/// Cells of a shape in reading order
+///
+/// `size` is the radius (spiral, circle), amplitude (wave), or height in
+/// rows (heart, triangle). `aspect` is the height of a character cell
+/// relative to its width and stretches shapes horizontally so they keep
+/// their proportions on screen. Coordinates are `(row, col)` from the
+/// top-left corner and each cell appears at most once.
+pub fn path(shape: CalligramShape, size: usize, aspect: f64) -> Vec<(usize, usize)> {
+    let size = size.max(1) as f64;
+
+    let points = match shape {
+        CalligramShape::Spiral => spiral(size, aspect),
+        CalligramShape::Circle => rings(size, aspect),
+        CalligramShape::Wave => wave(size, aspect),
+        CalligramShape::Heart => heart(size, aspect),
+        CalligramShape::Triangle => triangle(size, aspect),
+    };
+
+    normalise(points)
+}
+
+/// Mask with the cells of a path switched on
+pub fn path_mask(path: &[(usize, usize)]) -> Mask {
+    let height = path.iter().map(|&(r, _)| r + 1).max().unwrap_or(0);
+    let width = path.iter().map(|&(_, c)| c + 1).max().unwrap_or(0);
+
+    let mut mask = vec![vec![false; width]; height];
+    for &(r, c) in path {
+        mask[r][c] = true;
+    }
+    mask
+}
+
+/// Shift points so the smallest row and column are zero, dropping repeats
+fn normalise(points: Vec<(i64, i64)>) -> Vec<(usize, usize)> {
+    let min_row = points.iter().map(|&(r, _)| r).min().unwrap_or(0);
+    let min_col = points.iter().map(|&(_, c)| c).min().unwrap_or(0);
+
+    let mut seen = HashSet::new();
+    points
+        .into_iter()
+        .filter(|point| seen.insert(*point))
+        .map(|(r, c)| ((r - min_row) as usize, (c - min_col) as usize))
+        .collect()
+}
+
+/// Round a point given in rows, with the column stretched by `aspect`
+fn cell(y: f64, x: f64, aspect: f64) -> (i64, i64) {
+    (y.round() as i64, (x * aspect).round() as i64)
+}
+
+fn spiral(radius: f64, aspect: f64) -> Vec<(i64, i64)> {
+    let mut points = Vec::new();
+    let mut theta: f64 = 0.0;
+
+    loop {
+        let r = theta * LINE_GAP / TAU;
+        if r > radius {
+            break;
+        }
+        points.push(cell(r * theta.sin(), r * theta.cos(), aspect));
+        // Keep steps below half a cell so no cell along the curve is skipped
+        theta += 0.5 / (r * aspect).max(1.0);
+    }
+
+    points
+}
+
+fn rings(radius: f64, aspect: f64) -> Vec<(i64, i64)> {
+    let mut points = Vec::new();
+    let mut r = radius;
+
+    while r >= 1.0 {
+        let mut theta: f64 = 0.0;
+        while theta < TAU {
+            // Start at the top and go clockwise
+            let angle = theta - PI / 2.0;
+            points.push(cell(r * angle.sin(), r * angle.cos(), aspect));
+            theta += 0.5 / (r * aspect).max(1.0);
+        }
+        r -= LINE_GAP;
+    }
+
+    points
+}
+
+fn wave(amplitude: f64, aspect: f64) -> Vec<(i64, i64)> {
+    let period = (4.0 * amplitude * aspect).round().max(4.0);
+    let frequency = TAU / period;
+
+    let mut points = Vec::new();
+    let mut x: f64 = 0.0;
+    while x < 2.0 * period {
+        let y = -amplitude * (frequency * x).sin();
+        points.push((y.round() as i64, x as i64));
+        // Smaller steps where the curve is steep, so it stays connected
+        let slope = amplitude * frequency * (frequency * x).cos();
+        x += 0.5 / (1.0 + slope * slope).sqrt();
+    }
+
+    points
+}
+
+fn heart(height: f64, aspect: f64) -> Vec<(i64, i64)> {
+    // The curve (x² + y² - 1)³ = x²y³ spans about 2.25 units vertically
+    let unit = height / 2.25;
+    let rows = height.round() as i64;
+    let half_width = (1.2 * unit * aspect).ceil() as i64;
+
+    let mut points = Vec::new();
+    for row in 0..rows {
+        let y = 1.25 - (row as f64 + 0.5) / unit;
+        for col in -half_width..=half_width {
+            let x = col as f64 / (unit * aspect);
+            let a = x * x + y * y - 1.0;
+            if a * a * a - x * x * y * y * y <= 0.0 {
+                points.push((row, col));
+            }
+        }
+    }
+
+    points
+}
+
+fn triangle(height: f64, aspect: f64) -> Vec<(i64, i64)> {
+    let rows = height.round() as i64;
+
+    (0..rows)
+        .flat_map(|row| {
+            let half = (row as f64 * aspect / 2.0).round() as i64;
+            (-half..=half).map(move |col| (row, col))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shape() {
+        assert_eq!(CalligramShape::parse("Spiral"), Ok(CalligramShape::Spiral));
+        assert_eq!(CalligramShape::parse("rings"), Ok(CalligramShape::Circle));
+        assert_eq!(
+            CalligramShape::parse("pyramid"),
+            Ok(CalligramShape::Triangle)
+        );
+        assert!(CalligramShape::parse("blob").is_err());
+    }
+
+    #[test]
+    fn test_paths_have_unique_cells() {
+        for shape in [
+            CalligramShape::Spiral,
+            CalligramShape::Circle,
+            CalligramShape::Wave,
+            CalligramShape::Heart,
+            CalligramShape::Triangle,
+        ] {
+            let cells = path(shape, 6, 2.0);
+            let unique: HashSet<_> = cells.iter().collect();
+            assert!(!cells.is_empty());
+            assert_eq!(unique.len(), cells.len(), "{:?}", shape);
+            assert!(cells.iter().any(|&(r, _)| r == 0));
+            assert!(cells.iter().any(|&(_, c)| c == 0));
+        }
+    }
+
+    #[test]
+    fn test_curves_step_between_neighbours() {
+        for shape in [CalligramShape::Spiral, CalligramShape::Wave] {
+            let cells = path(shape, 8, 2.0);
+            for pair in cells.windows(2) {
+                let dr = pair[0].0.abs_diff(pair[1].0);
+                let dc = pair[0].1.abs_diff(pair[1].1);
+                assert!(dr <= 1 && dc <= 1, "{:?} {:?}", shape, pair);
+            }
+        }
+    }
+
+    #[test]
+    fn test_triangle_rows() {
+        let mask = path_mask(&path(CalligramShape::Triangle, 4, 2.0));
+        let widths: Vec<usize> = mask
+            .iter()
+            .map(|row| row.iter().filter(|&&on| on).count())
+            .collect();
+        assert_eq!(widths, vec![1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_wave_reads_left_to_right() {
+        let cells = path(CalligramShape::Wave, 3, 2.0);
+        assert!(cells.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        let mask = path_mask(&cells);
+        assert_eq!(mask.len(), 7);
+        assert_eq!(mask[0].len(), 48);
+    }
+
+    #[test]
+    fn test_heart_is_symmetric() {
+        let mask = path_mask(&path(CalligramShape::Heart, 10, 2.0));
+        assert_eq!(mask.len(), 10);
+        for row in &mask {
+            let reversed: Vec<bool> = row.iter().rev().copied().collect();
+            assert_eq!(row, &reversed);
+        }
+        // Two lobes on top, a single point at the bottom
+        assert!(!mask[0][mask[0].len() / 2]);
+        assert_eq!(mask[9].iter().filter(|&&on| on).count(), 1);
+    }
+}
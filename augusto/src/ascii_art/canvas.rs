@@ -0,0 +1,284 @@
+//! Canvas: the intermediate model behind every word art renderer
+//!
+//! A [`Canvas`] is a fixed-size grid of optional [`Cell`]s. Each cell carries
+//! the character to draw, its [`Style`], and provenance metadata: the
+//! main-word letter it belongs to, its position in the filler, and the
+//! [`Layer`] it was drawn on. Word art is composed by drawing into a canvas;
+//! the text, ANSI, SVG, HTML and raster renderers all read from one.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::word_art_canvas;
+//!
+//! let canvas = word_art_canvas("HI", "rust", 1);
+//! assert_eq!(canvas.height(), 5);
+//! print!("{}", canvas.to_text());
+//! ```
+
+/// An RGB colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parse a `#rrggbb` or `#rgb` hex colour (the `#` is optional)
+    pub fn parse_hex(text: &str) -> Result<Self, String> {
+        let hex = text.strip_prefix('#').unwrap_or(text);
+        let invalid = || format!("Invalid colour '{}'. Expected #rrggbb.", text);
+        // Channels are sliced by byte below
+        if !hex.is_ascii() {
+            return Err(invalid());
+        }
+
+        let expanded: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return Err(invalid()),
+        };
+
+        let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).map_err(|_| invalid());
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// CSS hex notation, e.g. `#ff8000`
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// The standard xterm values of the 16 basic terminal colours
+const ANSI_PALETTE: [Rgb; 16] = [
+    Rgb(0, 0, 0),
+    Rgb(205, 0, 0),
+    Rgb(0, 205, 0),
+    Rgb(205, 205, 0),
+    Rgb(0, 0, 238),
+    Rgb(205, 0, 205),
+    Rgb(0, 205, 205),
+    Rgb(229, 229, 229),
+    Rgb(127, 127, 127),
+    Rgb(255, 0, 0),
+    Rgb(0, 255, 0),
+    Rgb(255, 255, 0),
+    Rgb(92, 92, 255),
+    Rgb(255, 0, 255),
+    Rgb(0, 255, 255),
+    Rgb(255, 255, 255),
+];
+
+/// A foreground colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 basic terminal colours, by palette index
+    Ansi(u8),
+    /// A true colour
+    Rgb(Rgb),
+}
+
+impl Color {
+    /// RGB value of the colour, using the xterm palette for ANSI colours
+    pub fn to_rgb(self) -> Rgb {
+        match self {
+            Color::Ansi(index) => ANSI_PALETTE[index as usize % ANSI_PALETTE.len()],
+            Color::Rgb(rgb) => rgb,
+        }
+    }
+}
+
+/// Visual style of a cell
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    /// Foreground colour, or `None` for the renderer's default
+    pub fg: Option<Color>,
+}
+
+/// Which layer of the composition a cell was drawn on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Glyph strokes, drawn with the filler word
+    Stroke,
+    /// Drop shadow, drawn with the shadow filler
+    Shadow,
+}
+
+/// A single filled cell of the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character drawn in this cell
+    pub ch: char,
+    /// How the character is drawn
+    pub style: Style,
+    /// The main-word letter this cell belongs to
+    pub letter: char,
+    /// Index of that letter in the main word
+    pub letter_index: usize,
+    /// Position of `ch` within the filler word (or the shadow filler)
+    pub filler_index: usize,
+    /// Layer the cell was drawn on
+    pub layer: Layer,
+}
+
+/// A fixed-size grid of optional cells; `None` is blank space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Cell>>,
+}
+
+impl Canvas {
+    /// Create a blank canvas
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![None; width * height],
+        }
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `row`, `col`, if it is inside the canvas and filled
+    pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Draw a cell; positions outside the canvas are ignored
+    pub fn set(&mut self, row: usize, col: usize, cell: Cell) {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col] = Some(cell);
+        }
+    }
+
+    /// Blank a cell; positions outside the canvas are ignored
+    pub fn clear(&mut self, row: usize, col: usize) {
+        if row < self.height && col < self.width {
+            self.cells[row * self.width + col] = None;
+        }
+    }
+
+    /// Rows of cells, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Option<Cell>]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Mutable access to every filled cell with its `(row, col)`
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Cell)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(i, cell)| cell.as_mut().map(|c| (i / width, i % width, c)))
+    }
+
+    /// Draw another canvas on top of this one with its top-left at `top`, `left`
+    ///
+    /// Filled cells of `other` replace whatever is underneath; its blank cells
+    /// leave this canvas untouched.
+    pub fn overlay(&mut self, other: &Canvas, top: usize, left: usize) {
+        for (r, row) in other.rows().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                if let Some(cell) = cell {
+                    self.set(top + r, left + c, *cell);
+                }
+            }
+        }
+    }
+
+    /// Render as plain text, one line per row
+    pub fn to_text(&self) -> String {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.rows() {
+            for cell in row {
+                output.push(cell.map_or(' ', |c| c.ch));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(ch: char) -> Cell {
+        Cell {
+            ch,
+            style: Style::default(),
+            letter: 'A',
+            letter_index: 0,
+            filler_index: 0,
+            layer: Layer::Stroke,
+        }
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(Rgb::parse_hex("#ff8000"), Ok(Rgb(255, 128, 0)));
+        assert_eq!(Rgb::parse_hex("0f0"), Ok(Rgb(0, 255, 0)));
+        assert!(Rgb::parse_hex("#ff80").is_err());
+        assert!(Rgb::parse_hex("#gg0000").is_err());
+        assert!(Rgb::parse_hex("aéaaa").is_err());
+        assert!(Rgb::parse_hex("#éé").is_err());
+        assert_eq!(Rgb(255, 128, 0).to_hex(), "#ff8000");
+    }
+
+    #[test]
+    fn test_color_to_rgb() {
+        assert_eq!(Color::Ansi(9).to_rgb(), Rgb(255, 0, 0));
+        assert_eq!(Color::Rgb(Rgb(1, 2, 3)).to_rgb(), Rgb(1, 2, 3));
+    }
+
+    #[test]
+    fn test_set_and_get() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.set(1, 2, cell('x'));
+        canvas.set(5, 5, cell('y'));
+
+        assert_eq!(canvas.get(1, 2).map(|c| c.ch), Some('x'));
+        assert!(canvas.get(0, 0).is_none());
+        assert!(canvas.get(5, 5).is_none());
+        assert_eq!(canvas.to_text(), "   \n  x\n");
+
+        canvas.clear(1, 2);
+        canvas.clear(5, 5);
+        assert!(canvas.get(1, 2).is_none());
+    }
+
+    #[test]
+    fn test_overlay_layers() {
+        let mut base = Canvas::new(3, 1);
+        base.set(0, 0, cell('a'));
+        base.set(0, 1, cell('b'));
+
+        let mut top = Canvas::new(2, 1);
+        top.set(0, 1, cell('z'));
+
+        base.overlay(&top, 0, 1);
+        assert_eq!(base.to_text(), "abz\n");
+    }
+
+    #[test]
+    fn test_cells_mut_positions() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.set(1, 0, cell('q'));
+
+        let positions: Vec<(usize, usize)> = canvas.cells_mut().map(|(r, c, _)| (r, c)).collect();
+        assert_eq!(positions, vec![(1, 0)]);
+    }
+}
@@ -0,0 +1,296 @@
+//! Colour styling and ANSI terminal output for word art
+//!
+//! [`apply`] assigns a foreground colour to every cell of a [`Canvas`]
+//! according to a [`ColorMode`]; [`render_ansi`] then writes the canvas with
+//! ANSI colour escapes (via termion). Because colours live in the canvas
+//! styles, the SVG, HTML and raster renderers pick them up as well.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{word_art_canvas, color::{apply, render_ansi, ColorMode}};
+//!
+//! let mut canvas = word_art_canvas("LUXO", "LIXO", 1);
+//! apply(&mut canvas, ColorMode::Rainbow);
+//! println!("{}", render_ansi(&canvas));
+//! ```
+
+use std::{env, io};
+use termion::color;
+
+use super::canvas::{Canvas, Cell, Color, Rgb};
+
+/// Named terminal colours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl AnsiColor {
+    /// Look up a colour by its (case-insensitive) name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "red" => Some(AnsiColor::Red),
+            "green" => Some(AnsiColor::Green),
+            "yellow" => Some(AnsiColor::Yellow),
+            "blue" => Some(AnsiColor::Blue),
+            "magenta" => Some(AnsiColor::Magenta),
+            "cyan" => Some(AnsiColor::Cyan),
+            "white" => Some(AnsiColor::White),
+            _ => None,
+        }
+    }
+
+    /// The bright palette entry for this colour
+    fn color(self) -> Color {
+        Color::Ansi(match self {
+            AnsiColor::Red => 9,
+            AnsiColor::Green => 10,
+            AnsiColor::Yellow => 11,
+            AnsiColor::Blue => 12,
+            AnsiColor::Magenta => 13,
+            AnsiColor::Cyan => 14,
+            AnsiColor::White => 15,
+        })
+    }
+}
+
+/// Palette cycled by the per-filler and per-letter modes
+const PALETTE: [AnsiColor; 6] = [
+    AnsiColor::Red,
+    AnsiColor::Yellow,
+    AnsiColor::Green,
+    AnsiColor::Cyan,
+    AnsiColor::Blue,
+    AnsiColor::Magenta,
+];
+
+/// How filler characters are coloured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Plain text, no escapes
+    None,
+    /// Colour by the character's position in the filler word
+    Filler,
+    /// Colour by the main-word letter the character belongs to
+    Letter,
+    /// Horizontal rainbow gradient across the whole piece
+    Rainbow,
+    /// A single colour for every character
+    Fixed(AnsiColor),
+}
+
+impl ColorMode {
+    /// Parse a colour mode from its command-line name
+    ///
+    /// Accepts `none`, `filler`, `letter`, `rainbow` or a colour name
+    /// such as `red` for fixed-colour output.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "none" | "off" => Ok(ColorMode::None),
+            "filler" => Ok(ColorMode::Filler),
+            "letter" => Ok(ColorMode::Letter),
+            "rainbow" => Ok(ColorMode::Rainbow),
+            other => AnsiColor::from_name(other)
+                .map(ColorMode::Fixed)
+                .ok_or_else(|| format!("Unknown colour mode '{}'", name)),
+        }
+    }
+
+    /// Colour for a cell at the given column
+    fn color_for(self, cell: &Cell, column: usize, width: usize) -> Option<Color> {
+        match self {
+            ColorMode::None => None,
+            ColorMode::Filler => Some(PALETTE[cell.filler_index % PALETTE.len()].color()),
+            ColorMode::Letter => Some(PALETTE[cell.letter_index % PALETTE.len()].color()),
+            ColorMode::Rainbow => {
+                let hue = column as f64 / width.max(1) as f64 * 300.0;
+                Some(Color::Rgb(hue_to_rgb(hue)))
+            }
+            ColorMode::Fixed(c) => Some(c.color()),
+        }
+    }
+}
+
+/// Convert a hue in degrees to a fully saturated RGB colour
+fn hue_to_rgb(hue: f64) -> Rgb {
+    let h = (hue % 360.0) / 60.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+
+    Rgb(
+        (r * 255.0).round() as u8,
+        (g * 255.0).round() as u8,
+        (b * 255.0).round() as u8,
+    )
+}
+
+/// Whether coloured output should be written to stdout
+///
+/// Colour is disabled when stdout is not a terminal or when the
+/// `NO_COLOR` environment variable is set to a non-empty value.
+pub fn stdout_supports_color() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color && termion::is_tty(&io::stdout())
+}
+
+/// Set the foreground colour of every cell according to `mode`
+///
+/// [`ColorMode::None`] leaves existing styles untouched.
+pub fn apply(canvas: &mut Canvas, mode: ColorMode) {
+    if mode == ColorMode::None {
+        return;
+    }
+
+    let width = canvas.width();
+    for (_, column, cell) in canvas.cells_mut() {
+        cell.style.fg = mode.color_for(cell, column, width);
+    }
+}
+
+/// ANSI escape sequence that switches the foreground to `color`
+fn escape(color: Color) -> String {
+    match color {
+        Color::Ansi(index) => color::Fg(color::AnsiValue(index)).to_string(),
+        Color::Rgb(Rgb(r, g, b)) => color::Fg(color::Rgb(r, g, b)).to_string(),
+    }
+}
+
+/// Render a canvas with ANSI colour escapes taken from the cell styles
+///
+/// Escapes are only emitted when the colour changes, and every coloured
+/// line ends with a reset so the terminal is left in its default state.
+/// A canvas without colours renders exactly like [`Canvas::to_text`].
+pub fn render_ansi(canvas: &Canvas) -> String {
+    let mut output = String::new();
+
+    for row in canvas.rows() {
+        let mut current: Option<Color> = None;
+
+        for cell in row {
+            match cell {
+                Some(cell) => {
+                    if let Some(fg) = cell.style.fg {
+                        if current != Some(fg) {
+                            output.push_str(&escape(fg));
+                            current = Some(fg);
+                        }
+                    } else if current.take().is_some() {
+                        output.push_str(&color::Fg(color::Reset).to_string());
+                    }
+                    output.push(cell.ch);
+                }
+                None => output.push(' '),
+            }
+        }
+
+        if current.is_some() {
+            output.push_str(&color::Fg(color::Reset).to_string());
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::word_art_canvas;
+    use super::*;
+
+    fn strip_escapes(text: &str) -> String {
+        let mut output = String::new();
+        let mut in_escape = false;
+        for ch in text.chars() {
+            if ch == '\x1b' {
+                in_escape = true;
+            } else if in_escape {
+                if ch == 'm' {
+                    in_escape = false;
+                }
+            } else {
+                output.push(ch);
+            }
+        }
+        output
+    }
+
+    fn colorize(canvas: &Canvas, mode: ColorMode) -> String {
+        let mut canvas = canvas.clone();
+        apply(&mut canvas, mode);
+        render_ansi(&canvas)
+    }
+
+    #[test]
+    fn test_parse_color_mode() {
+        assert_eq!(ColorMode::parse("rainbow"), Ok(ColorMode::Rainbow));
+        assert_eq!(ColorMode::parse("Letter"), Ok(ColorMode::Letter));
+        assert_eq!(
+            ColorMode::parse("red"),
+            Ok(ColorMode::Fixed(AnsiColor::Red))
+        );
+        assert!(ColorMode::parse("sparkly").is_err());
+    }
+
+    #[test]
+    fn test_none_mode_is_plain_text() {
+        let canvas = word_art_canvas("HI", "rust", 1);
+        assert_eq!(colorize(&canvas, ColorMode::None), canvas.to_text());
+    }
+
+    #[test]
+    fn test_colorize_preserves_characters() {
+        let canvas = word_art_canvas("LUXO", "LIXO", 1);
+        for mode in [ColorMode::Filler, ColorMode::Letter, ColorMode::Rainbow] {
+            let colored = colorize(&canvas, mode);
+            assert!(colored.contains('\x1b'));
+            assert_eq!(strip_escapes(&colored), canvas.to_text());
+        }
+    }
+
+    #[test]
+    fn test_fixed_color_emits_single_escape_per_line() {
+        let canvas = word_art_canvas("A", "x", 1);
+        let colored = colorize(&canvas, ColorMode::Fixed(AnsiColor::Green));
+        let green = color::Fg(color::LightGreen).to_string();
+
+        for line in colored.lines() {
+            assert_eq!(line.matches(green.as_str()).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_apply_sets_cell_styles() {
+        let mut canvas = word_art_canvas("HI", "x", 1);
+        apply(&mut canvas, ColorMode::Letter);
+
+        assert_eq!(
+            canvas.get(0, 0).unwrap().style.fg,
+            Some(AnsiColor::Red.color())
+        );
+        assert_eq!(
+            canvas.get(0, 6).unwrap().style.fg,
+            Some(AnsiColor::Yellow.color())
+        );
+    }
+
+    #[test]
+    fn test_hue_to_rgb() {
+        assert_eq!(hue_to_rgb(0.0), Rgb(255, 0, 0));
+        assert_eq!(hue_to_rgb(120.0), Rgb(0, 255, 0));
+        assert_eq!(hue_to_rgb(240.0), Rgb(0, 0, 255));
+    }
+}
@@ -0,0 +1,368 @@
+//! Interactive glyph editor
+//!
+//! A termion-based editor for [`Font`] files. It shows one glyph at a time
+//! as a grid of cells that can be toggled with the keyboard, previews the
+//! glyph filled with a word, and saves the whole font in the format
+//! documented in [`font`](super::font).
+//!
+//! [`Editor`] holds the editing state and reacts to key presses; [`run`]
+//! connects it to the terminal.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{editor, font::Font};
+//!
+//! editor::run(Font::builtin(), "my.font", "augusto").unwrap();
+//! ```
+
+use std::io::{self, Write};
+
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::AlternateScreen;
+use termion::{clear, cursor, style};
+
+use super::fill::FillStrategy;
+use super::fill_mask;
+use super::font::Font;
+
+/// Key bindings shown at the bottom of the editor
+const HELP: &str = "arrows/hjkl move  space toggle  </> width  -/+ height  \
+                    tab next  g<char> pick glyph  s save  q quit";
+
+/// Editing state of the glyph editor
+#[derive(Debug, Clone)]
+pub struct Editor {
+    font: Font,
+    path: String,
+    filler: String,
+    current: char,
+    row: usize,
+    col: usize,
+    modified: bool,
+    picking: bool,
+    confirm_quit: bool,
+    status: String,
+}
+
+impl Editor {
+    /// Start editing `font`, which is saved to `path`
+    ///
+    /// The first glyph of the font is selected, or `A` if the font is empty.
+    pub fn new(font: Font, path: &str, filler: &str) -> Self {
+        let current = font.glyphs().next().map_or('A', |(ch, _)| ch);
+        Self {
+            font,
+            path: path.to_string(),
+            filler: filler.to_string(),
+            current,
+            row: 0,
+            col: 0,
+            modified: false,
+            picking: false,
+            confirm_quit: false,
+            status: String::new(),
+        }
+    }
+
+    /// The glyph being edited, at least one cell in size
+    fn glyph(&self) -> Vec<Vec<bool>> {
+        let glyph = self.font.glyph(self.current);
+        if glyph.is_empty() || glyph[0].is_empty() {
+            vec![vec![false]]
+        } else {
+            glyph
+        }
+    }
+
+    /// Store an edited glyph and keep the cursor inside it
+    fn update(&mut self, glyph: Vec<Vec<bool>>) {
+        self.row = self.row.min(glyph.len() - 1);
+        self.col = self.col.min(glyph[0].len() - 1);
+        self.font.set(self.current, glyph);
+        self.modified = true;
+    }
+
+    /// Select another glyph, creating a blank one if the font lacks it
+    fn select(&mut self, ch: char) {
+        if self.font.get(ch).is_none() {
+            let size = self.font.height();
+            self.font.set(ch, vec![vec![false; size]; size]);
+            self.modified = true;
+        }
+        self.current = ch;
+        self.row = 0;
+        self.col = 0;
+    }
+
+    /// Move to the next or previous glyph in character order
+    fn cycle(&mut self, forward: bool) {
+        let chars: Vec<char> = self.font.glyphs().map(|(ch, _)| ch).collect();
+        if let Some(index) = chars.iter().position(|&ch| ch == self.current) {
+            let next = if forward {
+                (index + 1) % chars.len()
+            } else {
+                (index + chars.len() - 1) % chars.len()
+            };
+            self.select(chars[next]);
+        }
+    }
+
+    /// React to a key press; returns `false` when the editor should close
+    pub fn handle(&mut self, key: Key) -> bool {
+        let confirm_quit = std::mem::take(&mut self.confirm_quit);
+        self.status.clear();
+
+        if self.picking {
+            self.picking = false;
+            if let Key::Char(ch) = key {
+                if ch != '\n' && ch != '\t' {
+                    self.select(ch);
+                }
+            }
+            return true;
+        }
+
+        let mut glyph = self.glyph();
+        let (height, width) = (glyph.len(), glyph[0].len());
+
+        match key {
+            Key::Up | Key::Char('k') => self.row = self.row.saturating_sub(1),
+            Key::Down | Key::Char('j') => self.row = (self.row + 1).min(height - 1),
+            Key::Left | Key::Char('h') => self.col = self.col.saturating_sub(1),
+            Key::Right | Key::Char('l') => self.col = (self.col + 1).min(width - 1),
+            Key::Char(' ') => {
+                glyph[self.row][self.col] = !glyph[self.row][self.col];
+                self.update(glyph);
+            }
+            Key::Char('>') => {
+                glyph.iter_mut().for_each(|row| row.push(false));
+                self.update(glyph);
+            }
+            Key::Char('<') if width > 1 => {
+                glyph.iter_mut().for_each(|row| {
+                    row.pop();
+                });
+                self.update(glyph);
+            }
+            Key::Char('+') => {
+                glyph.push(vec![false; width]);
+                self.update(glyph);
+            }
+            Key::Char('-') if height > 1 => {
+                glyph.pop();
+                self.update(glyph);
+            }
+            Key::Char('\t') => self.cycle(true),
+            Key::BackTab => self.cycle(false),
+            Key::Char('g') => {
+                self.picking = true;
+                self.status = "Type the character to edit".to_string();
+            }
+            Key::Char('s') => match self.font.save(&self.path) {
+                Ok(()) => {
+                    self.modified = false;
+                    self.status = format!("Saved {}", self.path);
+                }
+                Err(e) => self.status = e,
+            },
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => {
+                if !self.modified || confirm_quit || key == Key::Ctrl('c') {
+                    return false;
+                }
+                self.confirm_quit = true;
+                self.status = "Unsaved changes; press q again to quit".to_string();
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    /// The editor screen, one string per line
+    ///
+    /// The cell under the cursor is drawn in inverse video.
+    pub fn render(&self) -> Vec<String> {
+        let glyph = self.glyph();
+        let name = match self.current {
+            ' ' => "space".to_string(),
+            ch => format!("'{}'", ch),
+        };
+
+        let mut lines = vec![
+            format!(
+                "augusto font editor - {}{}",
+                self.path,
+                if self.modified { " [modified]" } else { "" }
+            ),
+            format!("Glyph {} ({}x{})", name, glyph[0].len(), glyph.len()),
+            String::new(),
+        ];
+
+        for (r, row) in glyph.iter().enumerate() {
+            let mut line = String::from("  ");
+            for (c, &on) in row.iter().enumerate() {
+                let cell = if on { '#' } else { '.' };
+                if (r, c) == (self.row, self.col) {
+                    line.push_str(&format!("{}{}{} ", style::Invert, cell, style::Reset));
+                } else {
+                    line.push(cell);
+                    line.push(' ');
+                }
+            }
+            lines.push(line);
+        }
+
+        lines.push(String::new());
+        lines.push(format!("Preview ({}):", self.filler));
+        let preview = fill_mask(&glyph, &self.filler, FillStrategy::Global);
+        lines.extend(preview.to_text().lines().map(|line| format!("  {}", line)));
+
+        lines.push(String::new());
+        lines.push(HELP.to_string());
+        lines.push(self.status.clone());
+        lines
+    }
+}
+
+/// Run the editor in the terminal until the user quits
+///
+/// The editor draws on the alternate screen in raw mode; both are restored
+/// when it closes, including after Ctrl-C.
+pub fn run(font: Font, path: &str, filler: &str) -> io::Result<()> {
+    if !termion::is_tty(&io::stdout()) {
+        return Err(io::Error::other("the font editor needs a terminal"));
+    }
+
+    let mut editor = Editor::new(font, path, filler);
+    // Dropping these restores the screen, the cursor and the terminal mode
+    let mut screen = cursor::HideCursor::from(AlternateScreen::from(io::stdout().into_raw_mode()?));
+    let mut keys = io::stdin().keys();
+
+    loop {
+        write!(screen, "{}{}", clear::All, cursor::Goto(1, 1))?;
+        for line in editor.render() {
+            write!(screen, "{}\r\n", line)?;
+        }
+        screen.flush()?;
+
+        match keys.next() {
+            Some(key) => {
+                if !editor.handle(key?) {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> Editor {
+        Editor::new(Font::builtin(), "test.font", "ab")
+    }
+
+    #[test]
+    fn test_toggle_and_move() {
+        let mut editor = editor();
+        assert_eq!(editor.current, 'A');
+        assert!(!editor.glyph()[0][0]);
+
+        editor.handle(Key::Char(' '));
+        assert!(editor.glyph()[0][0]);
+        assert!(editor.modified);
+
+        let before = Font::builtin().glyph('A')[1][1];
+        editor.handle(Key::Right);
+        editor.handle(Key::Char('j'));
+        editor.handle(Key::Char(' '));
+        assert_eq!(editor.glyph()[1][1], !before);
+        assert_eq!((editor.row, editor.col), (1, 1));
+
+        // The cursor stays inside the glyph
+        for _ in 0..10 {
+            editor.handle(Key::Left);
+            editor.handle(Key::Up);
+        }
+        assert_eq!((editor.row, editor.col), (0, 0));
+    }
+
+    #[test]
+    fn test_resize_glyph() {
+        let mut editor = editor();
+        editor.handle(Key::Char('>'));
+        editor.handle(Key::Char('+'));
+        assert_eq!(editor.glyph().len(), 6);
+        assert_eq!(editor.glyph()[0].len(), 6);
+
+        for _ in 0..10 {
+            editor.handle(Key::Char('<'));
+            editor.handle(Key::Char('-'));
+        }
+        assert_eq!(editor.glyph(), vec![vec![false]]);
+    }
+
+    #[test]
+    fn test_pick_and_cycle_glyphs() {
+        let mut editor = editor();
+        editor.handle(Key::Char('g'));
+        editor.handle(Key::Char('7'));
+        assert_eq!(editor.current, '7');
+        assert_eq!(editor.glyph(), vec![vec![false; 5]; 5]);
+
+        editor.handle(Key::Char('g'));
+        editor.handle(Key::Char('B'));
+        editor.handle(Key::Char('\t'));
+        assert_eq!(editor.current, 'C');
+        editor.handle(Key::BackTab);
+        editor.handle(Key::BackTab);
+        assert_eq!(editor.current, 'A');
+    }
+
+    #[test]
+    fn test_quit_asks_before_discarding_changes() {
+        let mut unchanged = editor();
+        assert!(!unchanged.handle(Key::Esc));
+
+        let mut changed = editor();
+        changed.handle(Key::Char(' '));
+        assert!(changed.handle(Key::Char('q')));
+        assert!(changed.render().last().unwrap().contains("Unsaved"));
+        assert!(!changed.handle(Key::Char('q')));
+        assert!(!changed.handle(Key::Ctrl('c')));
+    }
+
+    #[test]
+    fn test_save_writes_font_file() {
+        let path = std::env::temp_dir().join(format!("augusto-editor-{}.font", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut editor = Editor::new(Font::builtin(), path, "ab");
+        editor.handle(Key::Char(' '));
+        editor.handle(Key::Char('s'));
+        assert!(!editor.modified);
+
+        let saved = Font::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(saved.glyph('A')[0][0]);
+    }
+
+    #[test]
+    fn test_render_shows_grid_and_preview() {
+        let editor = editor();
+        let screen = editor.render();
+
+        assert_eq!(screen[1], "Glyph 'A' (5x5)");
+        assert!(screen[3].contains(&format!("{}.{}", style::Invert, style::Reset)));
+        assert_eq!(screen[4], "  # . . . # ");
+        assert!(screen.contains(&"Preview (ab):".to_string()));
+        assert!(screen.contains(&"   aba ".to_string()));
+    }
+}
@@ -0,0 +1,263 @@
+//! Glyph effects
+//!
+//! Effects reshape a glyph's masks before it is filled: outline-only,
+//! drop shadow, bold and inverse. They work on a [`Glyph`] (a stroke mask
+//! plus a shadow mask) and can be chained; each effect sees the result of
+//! the previous one.
+
+use super::fill::Mask;
+
+/// A glyph's stroke cells and drop-shadow cells, always the same size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    /// Cells drawn with the filler word
+    pub stroke: Mask,
+    /// Cells drawn with the shadow filler
+    pub shadow: Mask,
+}
+
+impl Glyph {
+    /// A glyph with the given strokes and no shadow
+    pub fn new(stroke: Mask) -> Self {
+        let shadow = stroke.iter().map(|row| vec![false; row.len()]).collect();
+        Self { stroke, shadow }
+    }
+
+    /// `(height, width)` of the glyph box
+    pub fn size(&self) -> (usize, usize) {
+        let width = self.stroke.iter().map(Vec::len).max().unwrap_or(0);
+        (self.stroke.len(), width)
+    }
+}
+
+/// A glyph-level effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Keep only stroke cells that touch blank space
+    Outline,
+    /// Cast a shadow offset by `dx` columns and `dy` rows
+    Shadow { dx: isize, dy: isize },
+    /// Thicken strokes by dilating them one cell to the right
+    Bold,
+    /// Fill the glyph box everywhere except the strokes
+    Inverse,
+}
+
+impl Effect {
+    /// Parse an effect from its command-line name
+    ///
+    /// `shadow` uses an offset of one cell right and one down.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "outline" => Ok(Effect::Outline),
+            "shadow" => Ok(Effect::Shadow { dx: 1, dy: 1 }),
+            "bold" => Ok(Effect::Bold),
+            "inverse" | "negative" => Ok(Effect::Inverse),
+            _ => Err(format!("Unknown effect '{}'", name)),
+        }
+    }
+}
+
+/// Whether `mask` is set at a possibly out-of-range position
+fn is_set(mask: &Mask, r: isize, c: isize) -> bool {
+    r >= 0
+        && c >= 0
+        && mask
+            .get(r as usize)
+            .and_then(|row| row.get(c as usize))
+            .copied()
+            .unwrap_or(false)
+}
+
+/// Copy `mask` into a larger blank mask at the given offset
+fn pad(mask: &Mask, height: usize, width: usize, top: usize, left: usize) -> Mask {
+    let mut output = vec![vec![false; width]; height];
+    for (r, row) in mask.iter().enumerate() {
+        for (c, &on) in row.iter().enumerate() {
+            output[top + r][left + c] = on;
+        }
+    }
+    output
+}
+
+/// Apply a single effect to a glyph
+pub fn apply(glyph: &Glyph, effect: Effect) -> Glyph {
+    let (height, width) = glyph.size();
+
+    match effect {
+        Effect::Outline => {
+            let stroke = (0..height as isize)
+                .map(|r| {
+                    (0..width as isize)
+                        .map(|c| {
+                            is_set(&glyph.stroke, r, c)
+                                && [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                                    .iter()
+                                    .any(|&(dr, dc)| !is_set(&glyph.stroke, r + dr, c + dc))
+                        })
+                        .collect()
+                })
+                .collect();
+            Glyph {
+                stroke,
+                shadow: glyph.shadow.clone(),
+            }
+        }
+        Effect::Shadow { dx, dy } => {
+            let new_height = height + dy.unsigned_abs();
+            let new_width = width + dx.unsigned_abs();
+            // The glyph moves away from negative offsets so the shadow fits
+            let (top, left) = (
+                if dy < 0 { dy.unsigned_abs() } else { 0 },
+                if dx < 0 { dx.unsigned_abs() } else { 0 },
+            );
+            let (shadow_top, shadow_left) =
+                ((top as isize + dy) as usize, (left as isize + dx) as usize);
+
+            let stroke = pad(&glyph.stroke, new_height, new_width, top, left);
+            let old_shadow = pad(&glyph.shadow, new_height, new_width, top, left);
+            let cast = pad(
+                &glyph.stroke,
+                new_height,
+                new_width,
+                shadow_top,
+                shadow_left,
+            );
+
+            let shadow = (0..new_height)
+                .map(|r| {
+                    (0..new_width)
+                        .map(|c| (old_shadow[r][c] || cast[r][c]) && !stroke[r][c])
+                        .collect()
+                })
+                .collect();
+            Glyph { stroke, shadow }
+        }
+        Effect::Bold => {
+            let dilate = |mask: &Mask| -> Mask {
+                (0..height as isize)
+                    .map(|r| {
+                        (0..=width as isize)
+                            .map(|c| is_set(mask, r, c) || is_set(mask, r, c - 1))
+                            .collect()
+                    })
+                    .collect()
+            };
+            let stroke = dilate(&glyph.stroke);
+            let shadow = dilate(&glyph.shadow)
+                .iter()
+                .zip(&stroke)
+                .map(|(shadow_row, stroke_row)| {
+                    shadow_row
+                        .iter()
+                        .zip(stroke_row)
+                        .map(|(&s, &t)| s && !t)
+                        .collect()
+                })
+                .collect();
+            Glyph { stroke, shadow }
+        }
+        Effect::Inverse => {
+            let stroke: Mask = glyph
+                .stroke
+                .iter()
+                .map(|row| row.iter().map(|&on| !on).collect())
+                .collect();
+            let shadow = glyph
+                .shadow
+                .iter()
+                .zip(&stroke)
+                .map(|(shadow_row, stroke_row)| {
+                    shadow_row
+                        .iter()
+                        .zip(stroke_row)
+                        .map(|(&s, &t)| s && !t)
+                        .collect()
+                })
+                .collect();
+            Glyph { stroke, shadow }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(lines: &[&str]) -> Mask {
+        lines
+            .iter()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    /// Render strokes as `#`, shadow as `.` and blanks as spaces
+    fn render(glyph: &Glyph) -> Vec<String> {
+        glyph
+            .stroke
+            .iter()
+            .zip(&glyph.shadow)
+            .map(|(stroke, shadow)| {
+                stroke
+                    .iter()
+                    .zip(shadow)
+                    .map(|(&t, &s)| match (t, s) {
+                        (true, _) => '#',
+                        (false, true) => '.',
+                        _ => ' ',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_effect() {
+        assert_eq!(Effect::parse("outline"), Ok(Effect::Outline));
+        assert_eq!(Effect::parse("Shadow"), Ok(Effect::Shadow { dx: 1, dy: 1 }));
+        assert_eq!(Effect::parse("negative"), Ok(Effect::Inverse));
+        assert!(Effect::parse("glow").is_err());
+    }
+
+    #[test]
+    fn test_outline_hollows_solid_shapes() {
+        let block = Glyph::new(mask(&["###", "###", "###"]));
+        assert_eq!(
+            render(&apply(&block, Effect::Outline)),
+            vec!["###", "# #", "###"]
+        );
+    }
+
+    #[test]
+    fn test_shadow_offsets() {
+        let dot = Glyph::new(mask(&["#"]));
+
+        let down_right = apply(&dot, Effect::Shadow { dx: 2, dy: 1 });
+        assert_eq!(render(&down_right), vec!["#  ", "  ."]);
+
+        let up_left = apply(&dot, Effect::Shadow { dx: -1, dy: -1 });
+        assert_eq!(render(&up_left), vec![". ", " #"]);
+    }
+
+    #[test]
+    fn test_bold_dilates_right() {
+        let bar = Glyph::new(mask(&["#  ", " # "]));
+        assert_eq!(render(&apply(&bar, Effect::Bold)), vec!["##  ", " ## "]);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let l = Glyph::new(mask(&["# ", "##"]));
+        assert_eq!(render(&apply(&l, Effect::Inverse)), vec![" #", "  "]);
+    }
+
+    #[test]
+    fn test_effects_compose() {
+        let dot = Glyph::new(mask(&["#"]));
+        let shadowed = apply(&dot, Effect::Shadow { dx: 1, dy: 0 });
+        let bold = apply(&shadowed, Effect::Bold);
+
+        // Bold thickens the stroke, which now covers part of the shadow
+        assert_eq!(render(&bold), vec!["##."]);
+    }
+}
@@ -0,0 +1,331 @@
+//! Filler distribution strategies
+//!
+//! A strategy decides which character of the filler word lands on each stroke
+//! cell of each glyph. The original behaviour ([`FillStrategy::Global`]) reads
+//! the filler word row by row across the whole piece; the other strategies
+//! make the filler read per letter, down columns, along strokes, at random,
+//! or use a different filler word for each letter.
+
+/// A glyph as a grid of stroke cells, `true` where the glyph is drawn
+pub type Mask = Vec<Vec<bool>>;
+
+/// How filler characters are distributed over the glyph strokes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillStrategy {
+    /// One filler sequence, row by row across all letters
+    Global,
+    /// Restart the filler word at every letter, row by row within the glyph
+    PerLetter,
+    /// Fill each glyph column by column, continuing from letter to letter
+    Columns,
+    /// Follow each glyph's strokes like a pen would, continuing between letters
+    Strokes,
+    /// Pick each filler character pseudo-randomly from the given seed
+    Random(u64),
+    /// Split the filler on commas and give each letter its own word
+    WordPerLetter,
+}
+
+impl FillStrategy {
+    /// Parse a strategy from its command-line name
+    ///
+    /// Accepts `global`, `letter`, `columns`, `strokes`, `words`, and
+    /// `random` or `random:<seed>`.
+    pub fn parse(name: &str) -> Result<Self, String> {
+        let lower = name.to_lowercase();
+        match lower.as_str() {
+            "global" => Ok(FillStrategy::Global),
+            "letter" | "per-letter" => Ok(FillStrategy::PerLetter),
+            "columns" | "column" => Ok(FillStrategy::Columns),
+            "strokes" | "stroke" => Ok(FillStrategy::Strokes),
+            "words" | "word-per-letter" => Ok(FillStrategy::WordPerLetter),
+            "random" => Ok(FillStrategy::Random(0)),
+            _ => match lower.strip_prefix("random:") {
+                Some(seed) => seed
+                    .parse::<u64>()
+                    .map(FillStrategy::Random)
+                    .map_err(|_| format!("Invalid random seed '{}'", seed)),
+                None => Err(format!("Unknown fill strategy '{}'", name)),
+            },
+        }
+    }
+
+    /// The filler words used by this strategy, as character lists
+    ///
+    /// Only [`FillStrategy::WordPerLetter`] splits the filler on commas;
+    /// empty words are dropped.
+    pub fn filler_words(&self, filler: &str) -> Vec<Vec<char>> {
+        let words: Vec<&str> = match self {
+            FillStrategy::WordPerLetter => filler.split(',').map(str::trim).collect(),
+            _ => vec![filler],
+        };
+
+        words
+            .into_iter()
+            .filter(|w| !w.is_empty())
+            .map(|w| w.chars().collect())
+            .collect()
+    }
+}
+
+/// Small deterministic xorshift generator for [`FillStrategy::Random`]
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        let state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        Self(if state == 0 { 1 } else { state })
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Order in which a pen would trace a glyph's strokes
+///
+/// Starts at the top-left-most stroke cell and keeps stepping to an unvisited
+/// neighbour (straight moves before diagonal ones); when stuck, it lifts the
+/// pen and restarts at the next unvisited cell.
+pub fn stroke_order(mask: &Mask) -> Vec<(usize, usize)> {
+    const NEIGHBOURS: [(isize, isize); 8] = [
+        (0, 1),
+        (1, 0),
+        (0, -1),
+        (-1, 0),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ];
+
+    let mut visited: Vec<Vec<bool>> = mask.iter().map(|row| vec![false; row.len()]).collect();
+    let mut order = Vec::new();
+
+    let is_open = |visited: &Vec<Vec<bool>>, r: usize, c: usize| {
+        mask.get(r).and_then(|row| row.get(c)).copied() == Some(true) && !visited[r][c]
+    };
+
+    for (start_row, line) in mask.iter().enumerate() {
+        for start_col in 0..line.len() {
+            if !is_open(&visited, start_row, start_col) {
+                continue;
+            }
+
+            let (mut r, mut c) = (start_row, start_col);
+            visited[r][c] = true;
+            order.push((r, c));
+
+            while let Some((nr, nc)) = NEIGHBOURS.iter().find_map(|&(dr, dc)| {
+                let nr = r.checked_add_signed(dr)?;
+                let nc = c.checked_add_signed(dc)?;
+                is_open(&visited, nr, nc).then_some((nr, nc))
+            }) {
+                r = nr;
+                c = nc;
+                visited[r][c] = true;
+                order.push((r, c));
+            }
+        }
+    }
+
+    order
+}
+
+/// Stroke cells of all glyphs as `(letter, row, column)`, in fill order
+fn visit_order(masks: &[Mask], strategy: FillStrategy) -> Vec<(usize, usize, usize)> {
+    let mut order = Vec::new();
+
+    match strategy {
+        FillStrategy::Global | FillStrategy::Random(_) => {
+            let height = masks.iter().map(Vec::len).max().unwrap_or(0);
+            for row in 0..height {
+                for (letter, mask) in masks.iter().enumerate() {
+                    let Some(line) = mask.get(row) else { continue };
+                    for (col, &on) in line.iter().enumerate() {
+                        if on {
+                            order.push((letter, row, col));
+                        }
+                    }
+                }
+            }
+        }
+        FillStrategy::PerLetter | FillStrategy::WordPerLetter => {
+            for (letter, mask) in masks.iter().enumerate() {
+                for (row, line) in mask.iter().enumerate() {
+                    for (col, &on) in line.iter().enumerate() {
+                        if on {
+                            order.push((letter, row, col));
+                        }
+                    }
+                }
+            }
+        }
+        FillStrategy::Columns => {
+            for (letter, mask) in masks.iter().enumerate() {
+                let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+                for col in 0..width {
+                    for (row, line) in mask.iter().enumerate() {
+                        if line.get(col).copied().unwrap_or(false) {
+                            order.push((letter, row, col));
+                        }
+                    }
+                }
+            }
+        }
+        FillStrategy::Strokes => {
+            for (letter, mask) in masks.iter().enumerate() {
+                for (row, col) in stroke_order(mask) {
+                    order.push((letter, row, col));
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// Assign a filler position to every stroke cell of every glyph
+///
+/// `word_lengths` holds the length of each filler word; letter `i` draws
+/// from word `i % word_lengths.len()`. The result mirrors `masks`, with
+/// `Some(position)` on stroke cells and `None` elsewhere.
+pub fn assign(
+    masks: &[Mask],
+    word_lengths: &[usize],
+    strategy: FillStrategy,
+) -> Vec<Vec<Vec<Option<usize>>>> {
+    let mut result: Vec<Vec<Vec<Option<usize>>>> = masks
+        .iter()
+        .map(|mask| mask.iter().map(|row| vec![None; row.len()]).collect())
+        .collect();
+
+    if word_lengths.is_empty() {
+        return result;
+    }
+
+    let restart_per_letter = matches!(
+        strategy,
+        FillStrategy::PerLetter | FillStrategy::WordPerLetter
+    );
+    let mut rng = match strategy {
+        FillStrategy::Random(seed) => Some(XorShift::new(seed)),
+        _ => None,
+    };
+    let mut counter = 0;
+    let mut current_letter = 0;
+
+    for (letter, row, col) in visit_order(masks, strategy) {
+        if restart_per_letter && letter != current_letter {
+            counter = 0;
+        }
+        current_letter = letter;
+
+        let length = word_lengths[letter % word_lengths.len()].max(1);
+        let position = match rng.as_mut() {
+            Some(rng) => (rng.next() % length as u64) as usize,
+            None => counter % length,
+        };
+
+        result[letter][row][col] = Some(position);
+        counter += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(lines: &[&str]) -> Mask {
+        lines
+            .iter()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    /// Flatten one letter's assignment row by row, skipping blanks
+    fn sequence(grid: &[Vec<Option<usize>>]) -> Vec<usize> {
+        grid.iter().flatten().filter_map(|&p| p).collect()
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(FillStrategy::parse("global"), Ok(FillStrategy::Global));
+        assert_eq!(FillStrategy::parse("Strokes"), Ok(FillStrategy::Strokes));
+        assert_eq!(FillStrategy::parse("random"), Ok(FillStrategy::Random(0)));
+        assert_eq!(
+            FillStrategy::parse("random:42"),
+            Ok(FillStrategy::Random(42))
+        );
+        assert!(FillStrategy::parse("random:abc").is_err());
+        assert!(FillStrategy::parse("spiral").is_err());
+    }
+
+    #[test]
+    fn test_filler_words() {
+        let words = FillStrategy::WordPerLetter.filler_words("LIXO, LUXO,");
+        assert_eq!(
+            words,
+            vec![vec!['L', 'I', 'X', 'O'], vec!['L', 'U', 'X', 'O']]
+        );
+
+        let words = FillStrategy::Global.filler_words("a,b");
+        assert_eq!(words, vec![vec!['a', ',', 'b']]);
+    }
+
+    #[test]
+    fn test_stroke_order_follows_strokes() {
+        let l = mask(&["#    ", "#    ", "#    ", "#    ", "#####"]);
+        assert_eq!(
+            stroke_order(&l),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (4, 1),
+                (4, 2),
+                (4, 3),
+                (4, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_per_letter_restarts() {
+        let i = mask(&["###"]);
+        let masks = vec![i.clone(), i];
+
+        let global = assign(&masks, &[4], FillStrategy::Global);
+        assert_eq!(sequence(&global[1]), vec![3, 0, 1]);
+
+        let per_letter = assign(&masks, &[4], FillStrategy::PerLetter);
+        assert_eq!(sequence(&per_letter[0]), vec![0, 1, 2]);
+        assert_eq!(sequence(&per_letter[1]), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_columns_order() {
+        let t = mask(&["##", "#."]);
+        let filled = assign(&[t], &[10], FillStrategy::Columns);
+        assert_eq!(filled[0], vec![vec![Some(0), Some(2)], vec![Some(1), None]]);
+    }
+
+    #[test]
+    fn test_random_is_seeded() {
+        let masks = vec![mask(&["#####", "#####"])];
+        let a = assign(&masks, &[7], FillStrategy::Random(1));
+        let b = assign(&masks, &[7], FillStrategy::Random(1));
+        let c = assign(&masks, &[7], FillStrategy::Random(2));
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(sequence(&a[0]).iter().all(|&p| p < 7));
+    }
+}
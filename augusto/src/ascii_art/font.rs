@@ -0,0 +1,524 @@
+//! Art fonts and the font file format
+//!
+//! A [`Font`] maps characters to glyph masks. The built-in font is the 5x5
+//! letter table; other fonts are loaded from plain-text font files, which
+//! the `font-editor` command writes.
+//!
+//! # File format
+//!
+//! ```text
+//! ; Lines starting with ';' are comments, an empty line ends a glyph
+//! glyph A
+//! .###.
+//! #...#
+//! #####
+//! #...#
+//! #...#
+//!
+//! glyph I
+//! width 3
+//! #####
+//! ..#..
+//! ..#..
+//! ..#..
+//! #####
+//!
+//! glyph space
+//! .....
+//!
+//! kern AV -1
+//! ```
+//!
+//! Each `glyph <char>` line starts a glyph (`space` names the space
+//! character) and is followed by its rows, `#` for a stroke cell and `.` (or
+//! a space) for a blank one, so a row of spaces is a blank row. The glyph
+//! ends at the next empty line or `glyph` line. Rows may differ in length;
+//! short rows are padded with blank cells. Lookups try the exact character first, then its
+//! uppercase form.
+//!
+//! Two more lines only matter for proportional rendering:
+//!
+//! - `width <n>` inside a glyph gives its proportional width, from 1 to 64:
+//!   the glyph is cut (or padded) to its `n` central columns. Glyphs without a width lose
+//!   their blank side columns instead.
+//! - `kern <pair> <n>` adds `n` columns (negative to tighten, at most 16
+//!   either way) between the two characters of `pair` when they follow each
+//!   other.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::font::Font;
+//!
+//! let font = Font::parse("glyph I\n#\n#\n#\n").unwrap();
+//! assert_eq!(font.glyph('i'), vec![vec![true]; 3]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use super::fill::Mask;
+use super::get_letter_pattern;
+
+/// Glyph height of the built-in font, also used for blank glyphs
+const DEFAULT_SIZE: usize = 5;
+
+/// Largest proportional `width` a font file may give a glyph
+const MAX_WIDTH: usize = 64;
+
+/// Largest `kern` amount, either way, a font file may give a pair
+const MAX_KERN: isize = 16;
+
+/// Proportional widths of built-in letters that look too wide at 5 columns
+const BUILTIN_WIDTHS: [(char, usize); 1] = [('I', 3)];
+
+/// Built-in pairs whose open sides leave room to tighten the gap
+const BUILTIN_KERNING: [(char, char, isize); 14] = [
+    ('A', 'T', -1),
+    ('A', 'V', -1),
+    ('A', 'W', -1),
+    ('A', 'Y', -1),
+    ('F', 'A', -1),
+    ('L', 'T', -1),
+    ('L', 'V', -1),
+    ('L', 'W', -1),
+    ('L', 'Y', -1),
+    ('P', 'A', -1),
+    ('T', 'A', -1),
+    ('V', 'A', -1),
+    ('W', 'A', -1),
+    ('Y', 'A', -1),
+];
+
+/// A set of glyph masks keyed by character, with proportional metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    glyphs: BTreeMap<char, Mask>,
+    widths: BTreeMap<char, usize>,
+    kerning: BTreeMap<(char, char), isize>,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Font {
+    /// An empty font
+    pub fn new() -> Self {
+        Self {
+            glyphs: BTreeMap::new(),
+            widths: BTreeMap::new(),
+            kerning: BTreeMap::new(),
+        }
+    }
+
+    /// The built-in 5x5 letters A to Z, with a few kerning pairs
+    pub fn builtin() -> Self {
+        let glyphs = ('A'..='Z')
+            .map(|c| {
+                let mask = get_letter_pattern(c)
+                    .iter()
+                    .map(|line| line.chars().map(|ch| ch == '#').collect())
+                    .collect();
+                (c, mask)
+            })
+            .collect();
+        Self {
+            glyphs,
+            widths: BUILTIN_WIDTHS.into_iter().collect(),
+            kerning: BUILTIN_KERNING
+                .into_iter()
+                .map(|(a, b, n)| ((a, b), n))
+                .collect(),
+        }
+    }
+
+    /// Parse a font from the text format described in the module docs
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut font = Self::new();
+        let mut current: Option<(char, Mask)> = None;
+
+        for (number, line) in text.lines().enumerate() {
+            // Spaces are blank cells, so only the line ending is stripped from rows
+            let line = line.trim_end_matches('\r');
+            let trimmed = line.trim_end();
+            if trimmed.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = trimmed.strip_prefix("glyph ") {
+                if let Some((ch, mask)) = current.take() {
+                    font.set(ch, mask);
+                }
+                let ch = parse_glyph_name(name.trim()).ok_or_else(|| {
+                    format!("Line {}: invalid glyph name '{}'", number + 1, name.trim())
+                })?;
+                current = Some((ch, Vec::new()));
+                continue;
+            }
+
+            if let Some(value) = trimmed.strip_prefix("width ") {
+                let width = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|w| (1..=MAX_WIDTH).contains(w));
+                match (current.as_ref(), width) {
+                    (Some(&(ch, _)), Some(width)) => {
+                        font.widths.insert(ch, width);
+                    }
+                    (None, _) => return Err(format!("Line {}: width outside a glyph", number + 1)),
+                    (_, None) => {
+                        return Err(format!(
+                            "Line {}: invalid width '{}'. Expected 1 to {}.",
+                            number + 1,
+                            value.trim(),
+                            MAX_WIDTH
+                        ))
+                    }
+                }
+                continue;
+            }
+
+            if let Some(value) = trimmed.strip_prefix("kern ") {
+                let (pair, amount) = parse_kerning(value)
+                    .filter(|&(_, amount)| (-MAX_KERN..=MAX_KERN).contains(&amount))
+                    .ok_or_else(|| {
+                        format!(
+                            "Line {}: invalid kerning '{}'. Expected a pair and -{} to {}.",
+                            number + 1,
+                            value.trim(),
+                            MAX_KERN,
+                            MAX_KERN
+                        )
+                    })?;
+                font.kerning.insert(pair, amount);
+                continue;
+            }
+
+            if line.is_empty() {
+                if let Some((ch, mask)) = current.take() {
+                    font.set(ch, mask);
+                }
+                continue;
+            }
+            if trimmed.is_empty() && current.is_none() {
+                continue;
+            }
+
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    '#' => Ok(true),
+                    '.' | ' ' => Ok(false),
+                    other => Err(format!(
+                        "Line {}: unexpected '{}' in glyph row",
+                        number + 1,
+                        other
+                    )),
+                })
+                .collect::<Result<Vec<bool>, String>>()?;
+
+            match current.as_mut() {
+                Some((_, mask)) => mask.push(row),
+                None => return Err(format!("Line {}: row outside a glyph", number + 1)),
+            }
+        }
+
+        if let Some((ch, mask)) = current {
+            font.set(ch, mask);
+        }
+        if font.glyphs.is_empty() {
+            return Err("Font has no glyphs".to_string());
+        }
+        Ok(font)
+    }
+
+    /// Read a font file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path, e))?;
+        Self::parse(&text).map_err(|e| format!("Invalid font '{}': {}", path, e))
+    }
+
+    /// Write the font to a file
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("Could not write '{}': {}", path, e))
+    }
+
+    /// Serialise in the text format described in the module docs
+    pub fn to_text(&self) -> String {
+        let mut output = String::from("; augusto font\n");
+
+        for (&ch, mask) in &self.glyphs {
+            let name = if ch == ' ' {
+                "space".to_string()
+            } else {
+                ch.to_string()
+            };
+            output.push_str(&format!("\nglyph {}\n", name));
+            if let Some(width) = self.widths.get(&ch) {
+                output.push_str(&format!("width {}\n", width));
+            }
+            for row in mask {
+                output.extend(row.iter().map(|&on| if on { '#' } else { '.' }));
+                output.push('\n');
+            }
+        }
+
+        if !self.kerning.is_empty() {
+            output.push('\n');
+        }
+        for (&(a, b), amount) in &self.kerning {
+            output.push_str(&format!("kern {}{} {}\n", a, b, amount));
+        }
+
+        output
+    }
+
+    /// The glyph for `ch`, falling back to its uppercase form
+    pub fn get(&self, ch: char) -> Option<&Mask> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&ch.to_ascii_uppercase()))
+    }
+
+    /// The glyph for `ch`, or a blank square for characters not in the font
+    pub fn glyph(&self, ch: char) -> Mask {
+        self.get(ch).cloned().unwrap_or_else(|| {
+            let size = self.height();
+            vec![vec![false; size]; size]
+        })
+    }
+
+    /// The glyph for `ch` cut to its proportional width
+    ///
+    /// A glyph with a `width` keeps its central columns (or is padded evenly
+    /// on both sides); any other glyph loses its blank side columns. Blank
+    /// glyphs, such as a space, are left as they are.
+    pub fn proportional_glyph(&self, ch: char) -> Mask {
+        let mask = self.glyph(ch);
+        let full = mask.first().map_or(0, Vec::len);
+
+        let (left, width) = match self
+            .widths
+            .get(&ch)
+            .or_else(|| self.widths.get(&ch.to_ascii_uppercase()))
+        {
+            Some(&width) => ((full as isize - width as isize) / 2, width),
+            None => {
+                let inked = |c: &usize| mask.iter().any(|row| row[*c]);
+                match ((0..full).find(inked), (0..full).rev().find(inked)) {
+                    (Some(first), Some(last)) => (first as isize, last - first + 1),
+                    _ => return mask,
+                }
+            }
+        };
+
+        mask.iter()
+            .map(|row| {
+                (left..left + width as isize)
+                    .map(|c| c >= 0 && row.get(c as usize) == Some(&true))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Extra columns between `a` and `b`, falling back to their uppercase forms
+    pub fn kerning(&self, a: char, b: char) -> isize {
+        self.kerning
+            .get(&(a, b))
+            .or_else(|| {
+                self.kerning
+                    .get(&(a.to_ascii_uppercase(), b.to_ascii_uppercase()))
+            })
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Add or replace a glyph; rows are padded to the same width
+    pub fn set(&mut self, ch: char, mut mask: Mask) {
+        let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut mask {
+            row.resize(width, false);
+        }
+        self.glyphs.insert(ch, mask);
+    }
+
+    /// Height of the tallest glyph, or the built-in size for an empty font
+    pub fn height(&self) -> usize {
+        self.glyphs
+            .values()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(DEFAULT_SIZE)
+    }
+
+    /// Every glyph in character order
+    pub fn glyphs(&self) -> impl Iterator<Item = (char, &Mask)> {
+        self.glyphs.iter().map(|(&ch, mask)| (ch, mask))
+    }
+}
+
+/// The pair and amount of a `kern` line, such as `AV -1`
+fn parse_kerning(value: &str) -> Option<((char, char), isize)> {
+    let mut parts = value.split_whitespace();
+    let (pair, amount) = (parts.next()?, parts.next()?.parse().ok()?);
+    let mut chars = pair.chars();
+    match (chars.next(), chars.next(), chars.next(), parts.next()) {
+        (Some(a), Some(b), None, None) => Some(((a, b), amount)),
+        _ => None,
+    }
+}
+
+/// The character named in a `glyph` line
+fn parse_glyph_name(name: &str) -> Option<char> {
+    if name == "space" {
+        return Some(' ');
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_matches_letter_table() {
+        let font = Font::builtin();
+        assert_eq!(font.glyphs().count(), 26);
+        assert_eq!(font.height(), 5);
+        assert_eq!(
+            font.glyph('l')[4],
+            vec![true, true, true, true, true],
+            "lowercase falls back to uppercase"
+        );
+        assert_eq!(font.glyph('?'), vec![vec![false; 5]; 5]);
+    }
+
+    #[test]
+    fn test_parse_font() {
+        let text = "; test font\nglyph T\n###\n.#\n.#.\n\nglyph space\n..\n";
+        let font = Font::parse(text).unwrap();
+
+        assert_eq!(
+            font.glyph('T'),
+            vec![
+                vec![true, true, true],
+                vec![false, true, false],
+                vec![false, true, false]
+            ]
+        );
+        assert_eq!(font.glyph(' '), vec![vec![false, false]]);
+        assert_eq!(font.height(), 3);
+    }
+
+    #[test]
+    fn test_parse_space_rows() {
+        let font = Font::parse("glyph space\n   \n   \n\nglyph -\n   \n###\n   \n").unwrap();
+        assert_eq!(font.glyph(' '), vec![vec![false; 3]; 2]);
+        assert_eq!(
+            font.glyph('-'),
+            vec![vec![false; 3], vec![true; 3], vec![false; 3]]
+        );
+
+        // An empty line ends the glyph, so later rows belong to none
+        assert!(Font::parse("glyph A\n#\n\n#\n").is_err());
+        let crlf = Font::parse("glyph A\r\n#\r\n  \r\n").unwrap();
+        assert_eq!(crlf.glyph('A'), vec![vec![true, false], vec![false, false]]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Font::parse("").is_err());
+        assert!(Font::parse("#.#\n").is_err());
+        assert!(Font::parse("glyph AB\n#\n").is_err());
+        assert!(Font::parse("glyph A\n#x#\n").is_err());
+        assert!(Font::parse("width 3\nglyph A\n#\n").is_err());
+        assert!(Font::parse("glyph A\nwidth 0\n#\n").is_err());
+        assert!(Font::parse("glyph A\n#\nkern A -1\n").is_err());
+        assert!(Font::parse("glyph A\n#\nkern AV x\n").is_err());
+
+        let error = Font::parse("glyph A\nwidth 100000\n#\n").unwrap_err();
+        assert!(error.starts_with("Line 2:"), "{}", error);
+        let error = Font::parse("glyph A\n#\nkern AV -9223372036854775808\n").unwrap_err();
+        assert!(error.starts_with("Line 3:"), "{}", error);
+        assert!(Font::parse("glyph A\n#\nkern AV 17\n").is_err());
+        assert!(Font::parse("glyph A\nwidth 64\n#\nkern AV -16\n").is_ok());
+    }
+
+    #[test]
+    fn test_parse_widths_and_kerning() {
+        let text = "glyph T\nwidth 1\n###\n.#.\nkern TT 2\nkern Tt -1\n";
+        let font = Font::parse(text).unwrap();
+
+        assert_eq!(font.proportional_glyph('T'), vec![vec![true], vec![true]]);
+        assert_eq!(font.kerning('T', 'T'), 2);
+        assert_eq!(font.kerning('T', 't'), -1);
+        assert_eq!(font.kerning('t', 't'), 2, "falls back to uppercase");
+        assert_eq!(font.kerning('T', 'A'), 0);
+    }
+
+    #[test]
+    fn test_proportional_glyphs() {
+        let font = Font::builtin();
+
+        // I has an explicit width and keeps its centre columns
+        assert_eq!(
+            font.proportional_glyph('i'),
+            vec![
+                vec![true, true, true],
+                vec![false, true, false],
+                vec![false, true, false],
+                vec![false, true, false],
+                vec![true, true, true],
+            ]
+        );
+        assert_eq!(font.proportional_glyph('M'), font.glyph('M'));
+        // Without a width, blank side columns are dropped
+        let narrow = Font::parse("glyph L\n.#..\n.##.\n").unwrap();
+        assert_eq!(
+            narrow.proportional_glyph('L'),
+            vec![vec![true, false], vec![true, true]]
+        );
+        // Blank glyphs keep their size
+        assert_eq!(font.proportional_glyph('?'), vec![vec![false; 5]; 5]);
+
+        let mut wide = Font::parse("glyph O\nwidth 4\n##\n").unwrap();
+        assert_eq!(
+            wide.proportional_glyph('O'),
+            vec![vec![false, true, true, false]]
+        );
+        wide.set('O', vec![vec![true; 2]]);
+        assert_eq!(wide.proportional_glyph('O')[0].len(), 4);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut font = Font::builtin();
+        font.set(' ', vec![vec![false; 3]; 5]);
+        font.set('a', vec![vec![true], vec![true, true]]);
+
+        let parsed = Font::parse(&font.to_text()).unwrap();
+        assert_eq!(parsed, font);
+        assert_eq!(parsed.glyph('a'), vec![vec![true, false], vec![true, true]]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("augusto-font-{}.font", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        Font::builtin().save(path).unwrap();
+        assert_eq!(Font::load(path).unwrap(), Font::builtin());
+        std::fs::remove_file(path).unwrap();
+
+        assert!(Font::load(path).is_err());
+    }
+}
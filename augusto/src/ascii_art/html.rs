@@ -0,0 +1,207 @@
+//! HTML export for word art
+//!
+//! This module turns an [`Canvas`] into a self-contained HTML document.
+//! Every filler character is wrapped in a `<span>` carrying classes for the
+//! main-word letter it belongs to (`l<index>`) and its position in the filler
+//! word (`f<index>`), so the result can be styled and animated with CSS.
+//! Drop-shadow cells also carry the class `s`.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{word_art_canvas, html::{render_html, HtmlOptions}};
+//!
+//! let canvas = word_art_canvas("LUXO", "LIXO", 1);
+//! let html = render_html(&canvas, &HtmlOptions::default());
+//! assert!(html.contains("class=\"c l0 f0\""));
+//! ```
+
+use super::canvas::{Canvas, Cell, Layer};
+use super::escape_markup;
+
+/// How the characters are laid out in the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlLayout {
+    /// A `<pre>` block with one line of text per row
+    Pre,
+    /// A CSS grid with one element per cell, blank cells included
+    Grid,
+}
+
+impl HtmlLayout {
+    /// Parse a layout from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "pre" => Ok(HtmlLayout::Pre),
+            "grid" => Ok(HtmlLayout::Grid),
+            _ => Err(format!("Unknown HTML layout '{}'", name)),
+        }
+    }
+}
+
+/// Options for HTML output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Document layout
+    pub layout: HtmlLayout,
+    /// Contents of the `<title>` element
+    pub title: String,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            layout: HtmlLayout::Pre,
+            title: "augusto".to_string(),
+        }
+    }
+}
+
+/// Span element for a filled cell
+fn cell_span(cell: &Cell) -> String {
+    format!(
+        "<span class=\"c{} l{} f{}\"{}>{}</span>",
+        if cell.layer == Layer::Shadow {
+            " s"
+        } else {
+            ""
+        },
+        cell.letter_index,
+        cell.filler_index,
+        cell.style
+            .fg
+            .map(|fg| format!(" style=\"color:{}\"", fg.to_rgb().to_hex()))
+            .unwrap_or_default(),
+        escape_markup(&cell.ch.to_string())
+    )
+}
+
+/// Render a canvas as a standalone HTML document
+///
+/// Filled cells are emitted as `<span class="c l{letter} f{filler}">`
+/// (with an extra `s` class for shadow cells and an inline `color` when
+/// the cell is styled).
+/// In [`HtmlLayout::Grid`] blank cells are emitted as `<span class="b">`
+/// so every element keeps its row and column.
+pub fn render_html(canvas: &Canvas, options: &HtmlOptions) -> String {
+    let mut output = String::new();
+
+    output.push_str("<!DOCTYPE html>\n");
+    output.push_str("<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str(&format!(
+        "<title>{}</title>\n",
+        escape_markup(&options.title)
+    ));
+    output.push_str("<style>\n");
+    output.push_str(".art { font-family: monospace; line-height: 1.2; }\n");
+    if options.layout == HtmlLayout::Grid {
+        output.push_str(&format!(
+            ".art {{ display: grid; grid-template-columns: repeat({}, 1ch); }}\n",
+            canvas.width()
+        ));
+        output.push_str(".art span { text-align: center; }\n");
+    }
+    output.push_str("</style>\n</head>\n<body>\n");
+
+    match options.layout {
+        HtmlLayout::Pre => {
+            output.push_str("<pre class=\"art\">");
+            for row in canvas.rows() {
+                for cell in row {
+                    match cell {
+                        Some(cell) => output.push_str(&cell_span(cell)),
+                        None => output.push(' '),
+                    }
+                }
+                output.push('\n');
+            }
+            output.push_str("</pre>\n");
+        }
+        HtmlLayout::Grid => {
+            let width = canvas.width();
+            output.push_str("<div class=\"art\">\n");
+            for row in canvas.rows() {
+                for column in 0..width {
+                    match row.get(column).and_then(Option::as_ref) {
+                        Some(cell) => output.push_str(&cell_span(cell)),
+                        None => output.push_str("<span class=\"b\"></span>"),
+                    }
+                }
+                output.push('\n');
+            }
+            output.push_str("</div>\n");
+        }
+    }
+
+    output.push_str("</body>\n</html>\n");
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::word_art_canvas;
+    use super::*;
+
+    #[test]
+    fn test_html_pre_layout() {
+        let canvas = word_art_canvas("HI", "ab", 1);
+        let html = render_html(&canvas, &HtmlOptions::default());
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<pre class=\"art\">"));
+        // First stroke of H, and first stroke of I
+        assert!(html.contains("<span class=\"c l0 f0\">a</span>"));
+        assert!(html.contains("class=\"c l1 "));
+    }
+
+    #[test]
+    fn test_html_span_per_filled_cell() {
+        let canvas = word_art_canvas("A", "x", 1);
+        let html = render_html(&canvas, &HtmlOptions::default());
+
+        let filled = canvas.to_text().chars().filter(|&c| c == 'x').count();
+        assert_eq!(html.matches("<span class=\"c ").count(), filled);
+    }
+
+    #[test]
+    fn test_html_marks_shadow_cells() {
+        let style = super::super::ArtStyle {
+            effects: vec![super::super::effects::Effect::Shadow { dx: 1, dy: 1 }],
+            ..super::super::ArtStyle::default()
+        };
+        let canvas = super::super::word_art_styled("I", "o", &style);
+        let html = render_html(&canvas, &HtmlOptions::default());
+
+        assert!(html.contains("<span class=\"c s l0 f0\">.</span>"));
+        assert!(html.contains("<span class=\"c l0 f0\">o</span>"));
+    }
+
+    #[test]
+    fn test_html_grid_layout() {
+        let canvas = word_art_canvas("I", "o", 1);
+        let options = HtmlOptions {
+            layout: HtmlLayout::Grid,
+            title: "I".to_string(),
+        };
+        let html = render_html(&canvas, &options);
+
+        assert!(html.contains("grid-template-columns: repeat(5, 1ch)"));
+        // Every one of the 25 cells is present
+        let spans = html.matches("<span class=").count();
+        assert_eq!(spans, 25);
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let canvas = word_art_canvas("I", "<", 1);
+        let options = HtmlOptions {
+            layout: HtmlLayout::Pre,
+            title: "a & b".to_string(),
+        };
+        let html = render_html(&canvas, &options);
+
+        assert!(html.contains("<title>a &amp; b</title>"));
+        assert!(html.contains(">&lt;</span>"));
+    }
+}
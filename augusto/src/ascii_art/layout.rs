@@ -0,0 +1,285 @@
+//! Letter layouts and whole-canvas transforms
+//!
+//! A [`Layout`] decides where each letter's glyph is placed on the canvas
+//! (left to right, stacked, as a staircase, right to left). A [`Transform`]
+//! then mirrors or rotates the composed canvas as a whole. Transforms move
+//! cells around; the filler characters themselves are unchanged.
+
+use super::canvas::Canvas;
+
+/// Where letters are placed relative to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Left to right on one line (the default)
+    Horizontal,
+    /// Stacked top to bottom
+    Vertical,
+    /// Each letter one glyph down and one glyph right of the previous one
+    Diagonal,
+    /// Right to left on one line
+    RightToLeft,
+}
+
+/// A transform applied to the whole composed canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Mirror left to right
+    MirrorHorizontal,
+    /// Mirror top to bottom
+    MirrorVertical,
+    /// Rotate 90 degrees clockwise
+    Rotate90,
+    /// Rotate 180 degrees
+    Rotate180,
+    /// Rotate 270 degrees clockwise (90 counter-clockwise)
+    Rotate270,
+}
+
+/// Parse a `--layout` value into a layout and a list of transforms
+///
+/// The value is a comma-separated list holding at most one layout
+/// (`horizontal`, `vertical`, `diagonal`, `rtl`) and any number of
+/// transforms (`mirror-h`, `mirror-v`, `rotate-90`, `rotate-180`,
+/// `rotate-270`), applied in order. For example `vertical,rotate-90`.
+pub fn parse_layout(spec: &str) -> Result<(Layout, Vec<Transform>), String> {
+    let mut layout = None;
+    let mut transforms = Vec::new();
+
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parsed_layout = match part.to_lowercase().as_str() {
+            "horizontal" | "ltr" => Some(Layout::Horizontal),
+            "vertical" => Some(Layout::Vertical),
+            "diagonal" | "staircase" => Some(Layout::Diagonal),
+            "rtl" | "right-to-left" => Some(Layout::RightToLeft),
+            "mirror-h" => {
+                transforms.push(Transform::MirrorHorizontal);
+                None
+            }
+            "mirror-v" => {
+                transforms.push(Transform::MirrorVertical);
+                None
+            }
+            "rotate-90" => {
+                transforms.push(Transform::Rotate90);
+                None
+            }
+            "rotate-180" => {
+                transforms.push(Transform::Rotate180);
+                None
+            }
+            "rotate-270" => {
+                transforms.push(Transform::Rotate270);
+                None
+            }
+            _ => return Err(format!("Unknown layout '{}'", part)),
+        };
+
+        if let Some(parsed) = parsed_layout {
+            if layout.replace(parsed).is_some() {
+                return Err(format!("Only one layout may be given, found '{}'", spec));
+            }
+        }
+    }
+
+    Ok((layout.unwrap_or(Layout::Horizontal), transforms))
+}
+
+/// Top-left `(row, column)` of each letter's glyph on the canvas
+///
+/// `sizes` holds each glyph's `(height, width)`; `spacing` is the gap between
+/// consecutive letters along the layout direction. `kerning[i]` widens (or,
+/// when negative, narrows) the horizontal gap between letters `i` and `i + 1`,
+/// whichever side of the other they end up on, so for right-to-left layouts
+/// it should come from the font's pair `(i + 1, i)`; missing entries count
+/// as 0 and stacked letters are not kerned.
+pub fn letter_offsets(
+    sizes: &[(usize, usize)],
+    layout: Layout,
+    spacing: usize,
+    kerning: &[isize],
+) -> Vec<(usize, usize)> {
+    let mut offsets = vec![(0, 0); sizes.len()];
+    let (mut row, mut col) = (0, 0);
+
+    let order: Vec<usize> = match layout {
+        Layout::RightToLeft => (0..sizes.len()).rev().collect(),
+        _ => (0..sizes.len()).collect(),
+    };
+
+    for index in order {
+        let (height, width) = sizes[index];
+        offsets[index] = (row, col);
+
+        // The pair kerned is this letter and the one placed after it
+        let pair = match layout {
+            Layout::RightToLeft => index.checked_sub(1),
+            _ => Some(index),
+        };
+        let kern = pair.and_then(|i| kerning.get(i)).copied().unwrap_or(0);
+        let advance =
+            |col: usize| (col as isize + (width + spacing) as isize + kern).max(0) as usize;
+
+        match layout {
+            Layout::Horizontal | Layout::RightToLeft => col = advance(col),
+            Layout::Vertical => row += height + spacing,
+            Layout::Diagonal => {
+                row += height;
+                col = advance(col);
+            }
+        }
+    }
+
+    offsets
+}
+
+/// Apply a transform to a canvas
+pub fn transform(canvas: &Canvas, transform: Transform) -> Canvas {
+    let (height, width) = (canvas.height(), canvas.width());
+
+    let mut output = match transform {
+        Transform::Rotate90 | Transform::Rotate270 => Canvas::new(height, width),
+        _ => Canvas::new(width, height),
+    };
+
+    for (r, row) in canvas.rows().enumerate() {
+        for (c, cell) in row.iter().enumerate() {
+            let Some(cell) = cell else { continue };
+            let (to_row, to_col) = match transform {
+                Transform::MirrorHorizontal => (r, width - 1 - c),
+                Transform::MirrorVertical => (height - 1 - r, c),
+                Transform::Rotate180 => (height - 1 - r, width - 1 - c),
+                Transform::Rotate90 => (c, height - 1 - r),
+                Transform::Rotate270 => (width - 1 - c, r),
+            };
+            output.set(to_row, to_col, *cell);
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::canvas::{Cell, Layer, Style};
+    use super::*;
+
+    /// Build a canvas from text, with spaces as blank cells
+    fn grid(lines: &[&str]) -> Canvas {
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut canvas = Canvas::new(width, lines.len());
+        for (r, line) in lines.iter().enumerate() {
+            for (c, ch) in line.chars().enumerate() {
+                if ch != ' ' {
+                    canvas.set(
+                        r,
+                        c,
+                        Cell {
+                            ch,
+                            style: Style::default(),
+                            letter: 'A',
+                            letter_index: 0,
+                            filler_index: 0,
+                            layer: Layer::Stroke,
+                        },
+                    );
+                }
+            }
+        }
+        canvas
+    }
+
+    fn text(canvas: &Canvas) -> Vec<String> {
+        canvas.to_text().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        assert_eq!(parse_layout("vertical"), Ok((Layout::Vertical, vec![])));
+        assert_eq!(
+            parse_layout("rotate-90, mirror-h"),
+            Ok((
+                Layout::Horizontal,
+                vec![Transform::Rotate90, Transform::MirrorHorizontal]
+            ))
+        );
+        assert!(parse_layout("vertical,diagonal").is_err());
+        assert!(parse_layout("spiral").is_err());
+    }
+
+    #[test]
+    fn test_letter_offsets() {
+        let sizes = [(5, 5), (5, 5), (5, 5)];
+
+        assert_eq!(
+            letter_offsets(&sizes, Layout::Horizontal, 1, &[]),
+            vec![(0, 0), (0, 6), (0, 12)]
+        );
+        assert_eq!(
+            letter_offsets(&sizes, Layout::RightToLeft, 1, &[]),
+            vec![(0, 12), (0, 6), (0, 0)]
+        );
+        assert_eq!(
+            letter_offsets(&sizes, Layout::Vertical, 1, &[]),
+            vec![(0, 0), (6, 0), (12, 0)]
+        );
+        assert_eq!(
+            letter_offsets(&sizes, Layout::Diagonal, 0, &[]),
+            vec![(0, 0), (5, 5), (10, 10)]
+        );
+    }
+
+    #[test]
+    fn test_letter_offsets_kerning() {
+        let sizes = [(5, 5), (5, 3), (5, 5)];
+
+        assert_eq!(
+            letter_offsets(&sizes, Layout::Horizontal, 1, &[-1, 2]),
+            vec![(0, 0), (0, 5), (0, 11)]
+        );
+        assert_eq!(
+            letter_offsets(&sizes, Layout::RightToLeft, 1, &[-1, 2]),
+            vec![(0, 11), (0, 8), (0, 0)]
+        );
+        assert_eq!(
+            letter_offsets(&sizes, Layout::Vertical, 1, &[-1, 2]),
+            vec![(0, 0), (6, 0), (12, 0)]
+        );
+    }
+
+    #[test]
+    fn test_mirrors() {
+        let g = grid(&["ab ", "c  "]);
+        assert_eq!(
+            text(&transform(&g, Transform::MirrorHorizontal)),
+            vec![" ba", "  c"]
+        );
+        assert_eq!(
+            text(&transform(&g, Transform::MirrorVertical)),
+            vec!["c  ", "ab "]
+        );
+    }
+
+    #[test]
+    fn test_rotations() {
+        let g = grid(&["ab", "cd", "ef"]);
+        assert_eq!(
+            text(&transform(&g, Transform::Rotate90)),
+            vec!["eca", "fdb"]
+        );
+        assert_eq!(
+            text(&transform(&g, Transform::Rotate180)),
+            vec!["fe", "dc", "ba"]
+        );
+        assert_eq!(
+            text(&transform(&g, Transform::Rotate270)),
+            vec!["bdf", "ace"]
+        );
+
+        // Four quarter turns bring the canvas back
+        let mut turned = g.clone();
+        for _ in 0..4 {
+            turned = transform(&turned, Transform::Rotate90);
+        }
+        assert_eq!(turned, g);
+    }
+}
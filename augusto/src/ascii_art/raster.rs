@@ -0,0 +1,529 @@
+//! Raster (PNG and GIF) export for word art
+//!
+//! This module draws a [`Canvas`] into an RGBA bitmap using the embedded
+//! [`bitmap_font`](super::bitmap_font), then encodes it as PNG, or encodes a
+//! sequence of bitmaps as an animated GIF. No system fonts are involved, so
+//! the same input always produces the same bytes.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{word_art_canvas, raster::{rasterize, encode_png, RasterOptions}};
+//!
+//! let canvas = word_art_canvas("LUXO", "LIXO", 1);
+//! let image = rasterize(&canvas, &RasterOptions::default()).unwrap();
+//! let bytes = encode_png(&image).unwrap();
+//! ```
+
+use std::collections::HashMap;
+
+use super::bitmap_font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use super::canvas::{Canvas, Rgb};
+
+/// Largest [`RasterOptions::pixel_size`] accepted on the command line
+pub const MAX_PIXEL_SIZE: usize = 64;
+
+/// Largest [`RasterOptions::padding`] accepted on the command line
+pub const MAX_PADDING: usize = 4096;
+
+/// Options for raster output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RasterOptions {
+    /// Size in pixels of one font dot; each cell is `(5 + 1) x (7 + 1)` dots
+    pub pixel_size: usize,
+    /// Colour of the characters
+    pub foreground: Rgb,
+    /// Background colour, or `None` for a transparent background
+    pub background: Option<Rgb>,
+    /// Blank border around the art, in pixels
+    pub padding: usize,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            pixel_size: 2,
+            foreground: Rgb(0, 0, 0),
+            background: Some(Rgb(255, 255, 255)),
+            padding: 8,
+        }
+    }
+}
+
+/// An RGBA bitmap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    /// Width in pixels
+    pub width: usize,
+    /// Height in pixels
+    pub height: usize,
+    /// Row-major RGBA bytes, four per pixel
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Create an image filled with a single RGBA value
+    fn filled(width: usize, height: usize, rgba: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: rgba.repeat(width * height),
+        }
+    }
+
+    /// Fill an axis-aligned rectangle
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, rgba: [u8; 4]) {
+        for row in y..(y + h).min(self.height) {
+            for col in x..(x + w).min(self.width) {
+                let offset = (row * self.width + col) * 4;
+                self.pixels[offset..offset + 4].copy_from_slice(&rgba);
+            }
+        }
+    }
+
+    /// RGBA value of the pixel at (`x`, `y`)
+    #[cfg(test)]
+    fn get(&self, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * self.width + x) * 4;
+        [
+            self.pixels[offset],
+            self.pixels[offset + 1],
+            self.pixels[offset + 2],
+            self.pixels[offset + 3],
+        ]
+    }
+}
+
+/// Draw a canvas into an RGBA bitmap
+///
+/// Each cell is one glyph plus a one-dot gap to the right and below, scaled
+/// by `pixel_size`. Cells with a styled foreground use that colour instead
+/// of [`RasterOptions::foreground`]. Fails if the image would be too
+/// large to address.
+pub fn rasterize(canvas: &Canvas, options: &RasterOptions) -> Result<Image, String> {
+    let scale = options.pixel_size.max(1);
+    let side = |cells: usize, dots: usize| {
+        cells
+            .checked_mul(dots + 1)
+            .and_then(|n| n.checked_mul(scale))
+            .and_then(|n| n.checked_add(options.padding.checked_mul(2)?))
+    };
+    let (width, height) = match (
+        side(canvas.width(), GLYPH_WIDTH),
+        side(canvas.height(), GLYPH_HEIGHT),
+    ) {
+        (Some(width), Some(height))
+            if width
+                .checked_mul(height)
+                .and_then(|n| n.checked_mul(4))
+                .is_some() =>
+        {
+            (width, height)
+        }
+        _ => return Err("The image would be too large to draw".to_string()),
+    };
+    let cell_width = (GLYPH_WIDTH + 1) * scale;
+    let cell_height = (GLYPH_HEIGHT + 1) * scale;
+
+    let background = match options.background {
+        Some(Rgb(r, g, b)) => [r, g, b, 255],
+        None => [0, 0, 0, 0],
+    };
+
+    let mut image = Image::filled(width, height, background);
+
+    for (row_idx, row) in canvas.rows().enumerate() {
+        for (col_idx, cell) in row.iter().enumerate() {
+            let Some(cell) = cell else { continue };
+            let origin_x = options.padding + col_idx * cell_width;
+            let origin_y = options.padding + row_idx * cell_height;
+            let Rgb(r, g, b) = cell.style.fg.map_or(options.foreground, |fg| fg.to_rgb());
+            let foreground = [r, g, b, 255];
+
+            for y in 0..GLYPH_HEIGHT {
+                for x in 0..GLYPH_WIDTH {
+                    if bitmap_font::pixel(cell.ch, x, y) {
+                        image.fill_rect(
+                            origin_x + x * scale,
+                            origin_y + y * scale,
+                            scale,
+                            scale,
+                            foreground,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(image)
+}
+
+/// Encode a bitmap as PNG
+pub fn encode_png(image: &Image) -> Result<Vec<u8>, String> {
+    let (Ok(width), Ok(height)) = (u32::try_from(image.width), u32::try_from(image.height)) else {
+        return Err(format!(
+            "PNG encoding failed: {}x{} is larger than {}x{}",
+            image.width,
+            image.height,
+            u32::MAX,
+            u32::MAX
+        ));
+    };
+    let mut bytes = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    writer
+        .write_image_data(&image.pixels)
+        .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("PNG encoding failed: {}", e))?;
+
+    Ok(bytes)
+}
+
+/// How the colours of an animation are reduced to a GIF palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// The exact colours used, or [`Palette::Web`] if there are too many
+    Auto,
+    /// The 216-colour web-safe cube
+    Web,
+    /// 255 shades of gray
+    Gray,
+}
+
+impl Palette {
+    /// Parse a palette from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "auto" => Ok(Palette::Auto),
+            "web" => Ok(Palette::Web),
+            "gray" | "grey" => Ok(Palette::Gray),
+            _ => Err(format!(
+                "Unknown palette '{}'. Expected auto, web or gray.",
+                name
+            )),
+        }
+    }
+}
+
+/// Options for animated GIF output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GifOptions {
+    /// Time each frame is shown, in hundredths of a second
+    pub delay: u16,
+    /// Number of times the animation plays, `0` for forever
+    pub loops: usize,
+    /// Colour reduction
+    pub palette: Palette,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        Self {
+            delay: 8,
+            loops: 0,
+            palette: Palette::Auto,
+        }
+    }
+}
+
+/// Maps opaque colours to palette indices
+enum Quantizer {
+    Exact(HashMap<[u8; 3], u8>),
+    Web,
+    Gray,
+}
+
+impl Quantizer {
+    /// Choose a quantizer for the frames and build its colour table
+    fn new(frames: &[Image], palette: Palette) -> (Self, Vec<u8>) {
+        if palette == Palette::Auto {
+            let mut colours = HashMap::new();
+            let mut table = Vec::new();
+            for px in frames.iter().flat_map(|f| f.pixels.chunks(4)) {
+                let rgb = [px[0], px[1], px[2]];
+                if px[3] != 0 && !colours.contains_key(&rgb) {
+                    // One index is kept free for transparency
+                    if colours.len() == 255 {
+                        return Self::new(frames, Palette::Web);
+                    }
+                    colours.insert(rgb, colours.len() as u8);
+                    table.extend_from_slice(&rgb);
+                }
+            }
+            return (Quantizer::Exact(colours), table);
+        }
+
+        match palette {
+            Palette::Gray => {
+                let table = (0..255u32)
+                    .flat_map(|i| [(i * 255 / 254) as u8; 3])
+                    .collect();
+                (Quantizer::Gray, table)
+            }
+            _ => {
+                let table = (0..216u32)
+                    .flat_map(|i| [i / 36, i / 6 % 6, i % 6].map(|c| (c * 51) as u8))
+                    .collect();
+                (Quantizer::Web, table)
+            }
+        }
+    }
+
+    fn index(&self, rgb: [u8; 3]) -> u8 {
+        let [r, g, b] = rgb.map(|c| c as u32);
+        match self {
+            Quantizer::Exact(colours) => colours[&rgb],
+            Quantizer::Web => {
+                let level = |c: u32| (c + 25) / 51;
+                (level(r) * 36 + level(g) * 6 + level(b)) as u8
+            }
+            Quantizer::Gray => {
+                let luma = (299 * r + 587 * g + 114 * b) / 1000;
+                (luma * 254 / 255) as u8
+            }
+        }
+    }
+}
+
+/// Encode bitmaps of equal size as an animated GIF
+pub fn encode_gif(frames: &[Image], options: &GifOptions) -> Result<Vec<u8>, String> {
+    let failed = |e: gif::EncodingError| format!("GIF encoding failed: {}", e);
+
+    let (width, height) = match frames.first() {
+        Some(first) => (first.width, first.height),
+        None => return Err("GIF encoding failed: no frames".to_string()),
+    };
+    if width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(format!(
+            "GIF encoding failed: {}x{} is larger than 65535x65535",
+            width, height
+        ));
+    }
+
+    let (quantizer, mut table) = Quantizer::new(frames, options.palette);
+    let has_transparency = frames
+        .iter()
+        .any(|f| f.pixels.chunks(4).any(|px| px[3] == 0));
+    let transparent = has_transparency.then(|| {
+        let index = (table.len() / 3) as u8;
+        table.extend_from_slice(&[0, 0, 0]);
+        index
+    });
+
+    let mut bytes = Vec::new();
+    {
+        let mut encoder =
+            gif::Encoder::new(&mut bytes, width as u16, height as u16, &table).map_err(failed)?;
+
+        // Without a loop extension viewers play the animation once
+        match options.loops {
+            0 => encoder.set_repeat(gif::Repeat::Infinite).map_err(failed)?,
+            1 => {}
+            n => encoder
+                .set_repeat(gif::Repeat::Finite((n - 1).min(u16::MAX as usize) as u16))
+                .map_err(failed)?,
+        }
+
+        for image in frames {
+            let buffer: Vec<u8> = image
+                .pixels
+                .chunks(4)
+                .map(|px| match transparent {
+                    Some(index) if px[3] == 0 => index,
+                    _ => quantizer.index([px[0], px[1], px[2]]),
+                })
+                .collect();
+
+            let frame = gif::Frame {
+                width: width as u16,
+                height: height as u16,
+                delay: options.delay,
+                transparent,
+                // Clear transparent frames so earlier ones do not show through
+                dispose: if transparent.is_some() {
+                    gif::DisposalMethod::Background
+                } else {
+                    gif::DisposalMethod::Keep
+                },
+                buffer: buffer.into(),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).map_err(failed)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::word_art_canvas;
+    use super::*;
+
+    #[test]
+    fn test_oversized_images_fail() {
+        let canvas = word_art_canvas("HI", "x", 1);
+        let options = RasterOptions {
+            pixel_size: usize::MAX / 4,
+            ..RasterOptions::default()
+        };
+        assert!(rasterize(&canvas, &options).is_err());
+
+        let wide = Image {
+            width: u32::MAX as usize + 1,
+            height: 1,
+            pixels: Vec::new(),
+        };
+        assert!(encode_png(&wide).is_err());
+    }
+
+    #[test]
+    fn test_rasterize_dimensions() {
+        let canvas = word_art_canvas("HI", "rust", 1);
+        let options = RasterOptions {
+            pixel_size: 1,
+            padding: 2,
+            ..RasterOptions::default()
+        };
+        let image = rasterize(&canvas, &options).unwrap();
+
+        // 11 cells of 6px plus padding, 5 cells of 8px plus padding
+        assert_eq!(image.width, 11 * 6 + 4);
+        assert_eq!(image.height, 5 * 8 + 4);
+        assert_eq!(image.pixels.len(), image.width * image.height * 4);
+    }
+
+    #[test]
+    fn test_rasterize_draws_glyphs() {
+        let canvas = word_art_canvas("I", "A", 1);
+        let options = RasterOptions {
+            pixel_size: 1,
+            padding: 0,
+            ..RasterOptions::default()
+        };
+        let image = rasterize(&canvas, &options).unwrap();
+
+        // Top row of the font's 'A' is ".XX.." in the first cell
+        assert_eq!(image.get(0, 0), [255, 255, 255, 255]);
+        assert_eq!(image.get(1, 0), [0, 0, 0, 255]);
+        // Cell (0, 1) of the letter I is blank
+        assert_eq!(image.get(1, 8), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_transparent_background() {
+        let canvas = word_art_canvas("I", "x", 1);
+        let options = RasterOptions {
+            background: None,
+            ..RasterOptions::default()
+        };
+        let image = rasterize(&canvas, &options).unwrap();
+        assert_eq!(image.get(0, 0), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_png_is_deterministic_and_decodable() {
+        let canvas = word_art_canvas("LUXO", "LIXO", 1);
+        let image = rasterize(&canvas, &RasterOptions::default()).unwrap();
+
+        let first = encode_png(&image).expect("encodes");
+        let second = encode_png(&image).expect("encodes");
+        assert_eq!(first, second);
+        assert!(first.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let decoder = png::Decoder::new(first.as_slice());
+        let mut reader = decoder.read_info().expect("valid PNG");
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).expect("frame");
+
+        assert_eq!(info.width as usize, image.width);
+        assert_eq!(info.height as usize, image.height);
+        assert_eq!(&buffer[..info.buffer_size()], image.pixels.as_slice());
+    }
+
+    fn decode_gif(bytes: &[u8]) -> (Vec<Vec<u8>>, Vec<u16>, usize) {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes).expect("valid GIF");
+
+        let mut frames = Vec::new();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().expect("frame") {
+            frames.push(frame.buffer.to_vec());
+            delays.push(frame.delay);
+        }
+        let colours = decoder.global_palette().map_or(0, |p| p.len() / 3);
+        (frames, delays, colours)
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        assert_eq!(Palette::parse("Web"), Ok(Palette::Web));
+        assert_eq!(Palette::parse("grey"), Ok(Palette::Gray));
+        assert!(Palette::parse("sepia").is_err());
+    }
+
+    #[test]
+    fn test_gif_round_trips_frames() {
+        let options = RasterOptions::default();
+        let frames: Vec<Image> = ["abc", "bca"]
+            .iter()
+            .map(|filler| rasterize(&word_art_canvas("HI", filler, 1), &options).unwrap())
+            .collect();
+        let gif_options = GifOptions {
+            delay: 5,
+            ..GifOptions::default()
+        };
+
+        let bytes = encode_gif(&frames, &gif_options).expect("encodes");
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!(bytes, encode_gif(&frames, &gif_options).unwrap());
+
+        let (decoded, delays, _) = decode_gif(&bytes);
+        assert_eq!(delays, vec![5, 5]);
+        assert_eq!(decoded[0], frames[0].pixels);
+        assert_eq!(decoded[1], frames[1].pixels);
+    }
+
+    #[test]
+    fn test_gif_palettes() {
+        let image = Image {
+            width: 3,
+            height: 1,
+            pixels: vec![250, 10, 10, 255, 20, 20, 20, 255, 0, 0, 0, 0],
+        };
+
+        let web = GifOptions {
+            palette: Palette::Web,
+            ..GifOptions::default()
+        };
+        let (decoded, _, colours) =
+            decode_gif(&encode_gif(std::slice::from_ref(&image), &web).unwrap());
+        assert!(colours >= 217);
+        assert_eq!(&decoded[0][..8], &[255, 0, 0, 255, 0, 0, 0, 255]);
+        assert_eq!(decoded[0][11], 0);
+
+        let gray = GifOptions {
+            palette: Palette::Gray,
+            ..GifOptions::default()
+        };
+        let (decoded, _, _) = decode_gif(&encode_gif(&[image], &gray).unwrap());
+        assert_eq!(decoded[0][0], decoded[0][1]);
+        assert_eq!(decoded[0][1], decoded[0][2]);
+    }
+
+    #[test]
+    fn test_gif_rejects_empty_animation() {
+        assert!(encode_gif(&[], &GifOptions::default()).is_err());
+    }
+}
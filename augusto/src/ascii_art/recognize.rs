@@ -0,0 +1,363 @@
+//! Glyph recognition: reading the main word back from an art block
+//!
+//! Any visible character counts as an "on" cell, so the filler word does not
+//! matter. Rows of art are split on blank lines, glyphs on blank columns,
+//! and every glyph is compared with each letter of a font, both scaled to
+//! the larger of the two sizes. Wide gaps are read as spaces. Glyphs of the
+//! font that are blank (such as a space) never match.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{word_art_styled, ArtStyle, font::Font, recognize::read_art};
+//!
+//! let art = word_art_styled("LUXO", "LIXO", &ArtStyle::default()).to_text();
+//! let reading = read_art(&art, &Font::builtin(), 0.8);
+//! assert_eq!(reading.text(), "LUXO");
+//! ```
+
+use super::fill::Mask;
+use super::font::Font;
+
+/// A glyph found in the art and the letter it was matched to
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphMatch {
+    /// Best matching letter, or `None` if no letter was close enough
+    pub letter: Option<char>,
+    /// Best letter even when below the confidence threshold
+    pub best_guess: char,
+    /// Fraction of cells agreeing with the best letter, from 0 to 1
+    pub confidence: f64,
+    /// Line of the art block the glyph starts on, from 0
+    pub row: usize,
+    /// Column of the art block the glyph starts on, from 0
+    pub column: usize,
+    /// The glyph's cells, trimmed to its bounding box
+    pub mask: Mask,
+}
+
+/// Something read from the art, in reading order
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    /// A glyph
+    Glyph(GlyphMatch),
+    /// A gap at least as wide as the glyphs are tall
+    Space,
+    /// The start of another band of art below the previous one
+    LineBreak,
+}
+
+/// The result of reading an art block
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reading {
+    /// Glyphs, spaces and line breaks in reading order
+    pub tokens: Vec<Token>,
+}
+
+impl Reading {
+    /// The text read, with `?` for unknown glyphs
+    pub fn text(&self) -> String {
+        self.tokens
+            .iter()
+            .map(|token| match token {
+                Token::Glyph(glyph) => glyph.letter.unwrap_or('?'),
+                Token::Space => ' ',
+                Token::LineBreak => '\n',
+            })
+            .collect()
+    }
+
+    /// Glyphs in reading order
+    pub fn glyphs(&self) -> impl Iterator<Item = &GlyphMatch> {
+        self.tokens.iter().filter_map(|token| match token {
+            Token::Glyph(glyph) => Some(glyph),
+            _ => None,
+        })
+    }
+
+    /// Mean confidence over all glyphs, 0 if there are none
+    pub fn confidence(&self) -> f64 {
+        let (sum, count) = self
+            .glyphs()
+            .fold((0.0, 0), |(sum, count), g| (sum + g.confidence, count + 1));
+        if count == 0 {
+            0.0
+        } else {
+            sum / count as f64
+        }
+    }
+
+    /// Human-readable report of the text, per-glyph confidence and unknown glyphs
+    pub fn format(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Text: {}\n", self.text().replace('\n', " / ")));
+        output.push_str(&format!("Confidence: {:.1}%\n", self.confidence() * 100.0));
+
+        output.push_str("\nGlyphs:\n");
+        for glyph in self.glyphs() {
+            output.push_str(&format!(
+                "  line {:>2}, column {:>3}: {}  {:>5.1}%{}\n",
+                glyph.row + 1,
+                glyph.column + 1,
+                glyph.letter.unwrap_or('?'),
+                glyph.confidence * 100.0,
+                match glyph.letter {
+                    Some(_) => String::new(),
+                    None => format!("  (closest: {})", glyph.best_guess),
+                }
+            ));
+        }
+
+        let unknown: Vec<&GlyphMatch> = self.glyphs().filter(|g| g.letter.is_none()).collect();
+        if !unknown.is_empty() {
+            output.push_str(&format!("\nUnknown glyphs: {}\n", unknown.len()));
+            for glyph in unknown {
+                output.push_str(&format!(
+                    "\n  line {}, column {}:\n",
+                    glyph.row + 1,
+                    glyph.column + 1
+                ));
+                for line in &glyph.mask {
+                    let cells: String = line.iter().map(|&on| if on { '#' } else { '.' }).collect();
+                    output.push_str(&format!("    {}\n", cells));
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Remove ANSI escape sequences such as colour codes
+fn strip_escapes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Skip up to and including the final letter of the sequence
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+/// Bounding box `(top, left, bottom, right)` of the on cells, exclusive ends
+fn bounds(mask: &Mask) -> Option<(usize, usize, usize, usize)> {
+    let mut result: Option<(usize, usize, usize, usize)> = None;
+    for (r, line) in mask.iter().enumerate() {
+        for (c, &on) in line.iter().enumerate() {
+            if on {
+                let (top, left, bottom, right) = result.unwrap_or((r, c, r + 1, c + 1));
+                result = Some((top.min(r), left.min(c), bottom.max(r + 1), right.max(c + 1)));
+            }
+        }
+    }
+    result
+}
+
+/// Copy of the region `[top, bottom) x [left, right)`
+fn crop(mask: &Mask, (top, left, bottom, right): (usize, usize, usize, usize)) -> Mask {
+    mask[top..bottom]
+        .iter()
+        .map(|line| (left..right).map(|c| line.get(c) == Some(&true)).collect())
+        .collect()
+}
+
+/// How well a glyph matches a font letter, from 0 to 1
+///
+/// Both are sampled with nearest-neighbour onto a grid at least as fine as
+/// each of them, so a glyph smaller than the letter is compared cell by cell
+/// with the whole letter rather than with a few of its cells. The fraction
+/// of agreeing cells is weighted down when the glyph is stretched more in
+/// one direction than the other.
+fn similarity(glyph: &Mask, letter: &Mask) -> f64 {
+    let (height, width) = (glyph.len(), glyph[0].len());
+    let (letter_height, letter_width) = (letter.len(), letter[0].len());
+    let (grid_height, grid_width) = (height.max(letter_height), width.max(letter_width));
+
+    let mut agree = 0;
+    for r in 0..grid_height {
+        for c in 0..grid_width {
+            let on = glyph[r * height / grid_height][c * width / grid_width];
+            if letter[r * letter_height / grid_height][c * letter_width / grid_width] == on {
+                agree += 1;
+            }
+        }
+    }
+
+    let sx = width as f64 / letter_width as f64;
+    let sy = height as f64 / letter_height as f64;
+    let aspect = sx.min(sy) / sx.max(sy);
+
+    agree as f64 / (grid_width * grid_height) as f64 * aspect
+}
+
+/// Match a glyph against every font letter, keeping the first best one
+fn classify(glyph: &Mask, font: &Font) -> (char, f64) {
+    font.glyphs()
+        .filter_map(|(ch, mask)| bounds(mask).map(|b| (ch, crop(mask, b))))
+        .map(|(ch, letter)| (ch, similarity(glyph, &letter)))
+        .fold(('?', 0.0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// Read the letters spelled by an art block
+///
+/// `font` holds the letters to match against; glyphs whose best match is
+/// below `min_confidence` (0 to 1) are reported as unknown.
+pub fn read_art(text: &str, font: &Font, min_confidence: f64) -> Reading {
+    let clean = strip_escapes(text);
+    let mask: Mask = clean
+        .lines()
+        .map(|line| line.chars().map(|c| !c.is_whitespace()).collect())
+        .collect();
+    let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+    let column_on = |rows: &[Vec<bool>], c: usize| rows.iter().any(|l| l.get(c) == Some(&true));
+
+    let mut tokens = Vec::new();
+    let mut row = 0;
+    while row < mask.len() {
+        if !mask[row].contains(&true) {
+            row += 1;
+            continue;
+        }
+
+        // A band runs until the next blank line
+        let top = row;
+        while row < mask.len() && mask[row].contains(&true) {
+            row += 1;
+        }
+        let band = &mask[top..row];
+        let band_height = band.len();
+
+        if !tokens.is_empty() {
+            tokens.push(Token::LineBreak);
+        }
+
+        let mut column = 0;
+        let mut gap_start = None;
+        while column < width {
+            if !column_on(band, column) {
+                gap_start.get_or_insert(column);
+                column += 1;
+                continue;
+            }
+
+            // Spaces are gaps at least as wide as the band is tall
+            if let Some(start) = gap_start.take() {
+                let after_glyph = matches!(tokens.last(), Some(Token::Glyph(_)));
+                if after_glyph && column - start >= band_height {
+                    tokens.push(Token::Space);
+                }
+            }
+
+            let left = column;
+            while column < width && column_on(band, column) {
+                column += 1;
+            }
+
+            let region: Mask = band
+                .iter()
+                .map(|line| (left..column).map(|c| line.get(c) == Some(&true)).collect())
+                .collect();
+            if let Some(b) = bounds(&region) {
+                let glyph = crop(&region, b);
+                let (best_guess, confidence) = classify(&glyph, font);
+                tokens.push(Token::Glyph(GlyphMatch {
+                    letter: (confidence >= min_confidence).then_some(best_guess),
+                    best_guess,
+                    confidence,
+                    row: top + b.0,
+                    column: left,
+                    mask: glyph,
+                }));
+            }
+        }
+    }
+
+    Reading { tokens }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{word_art_styled, word_art_with_spacing, ArtStyle};
+    use super::*;
+
+    #[test]
+    fn test_reads_plain_word_art() {
+        let art = word_art_with_spacing("LUXO", "LIXO", 1);
+        let reading = read_art(&art, &Font::builtin(), 0.8);
+
+        assert_eq!(reading.text(), "LUXO");
+        assert_eq!(reading.confidence(), 1.0);
+        let columns: Vec<usize> = reading.glyphs().map(|g| g.column).collect();
+        assert_eq!(columns, vec![0, 6, 12, 18]);
+    }
+
+    #[test]
+    fn test_reads_spaces_and_wide_spacing() {
+        let art = word_art_with_spacing("HI YOU", "x", 2);
+        let reading = read_art(&art, &Font::builtin(), 0.8);
+        assert_eq!(reading.text(), "HI YOU");
+    }
+
+    #[test]
+    fn test_reads_scaled_art() {
+        let style = ArtStyle {
+            scale: 2.0,
+            ..ArtStyle::default()
+        };
+        let art = word_art_styled("RUST", "code", &style).to_text();
+        let reading = read_art(&art, &Font::builtin(), 0.8);
+        assert_eq!(reading.text(), "RUST");
+    }
+
+    #[test]
+    fn test_ignores_colour_escapes() {
+        let art = "\x1b[38;5;9m#####\x1b[39m\n  #  \n  #  \n  #  \n  #  \n";
+        let reading = read_art(art, &Font::builtin(), 0.8);
+        assert_eq!(reading.text(), "T");
+    }
+
+    #[test]
+    fn test_reports_unknown_glyphs() {
+        let art = "# #\n # \n# #\n\n#####\n#    \n#### \n#    \n#####\n";
+        let reading = read_art(art, &Font::builtin(), 0.9);
+
+        assert_eq!(reading.text(), "?\nE");
+        let unknown = reading.glyphs().next().unwrap();
+        assert_eq!(unknown.letter, None);
+
+        let report = reading.format();
+        assert!(report.contains("Unknown glyphs: 1"));
+        assert!(report.contains("#.#"));
+    }
+
+    #[test]
+    fn test_stray_characters_are_unknown() {
+        let reading = read_art("a b c d\n", &Font::builtin(), 0.8);
+        assert_eq!(reading.text(), "? ? ? ?");
+        assert!(reading.glyphs().all(|g| g.confidence < 0.8));
+
+        let reading = read_art("ab\n", &Font::builtin(), 0.8);
+        assert_eq!(reading.text(), "?");
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let reading = read_art("   \n\n", &Font::builtin(), 0.8);
+        assert!(reading.tokens.is_empty());
+        assert_eq!(reading.confidence(), 0.0);
+    }
+}
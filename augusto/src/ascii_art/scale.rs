@@ -0,0 +1,194 @@
+//! Glyph scaling
+//!
+//! Resizes glyph masks by integer or fractional factors before they are
+//! filled, so a 5x5 letter at `--scale 3` becomes a 15x15 letter whose
+//! strokes are still drawn with the filler word.
+
+use super::fill::Mask;
+
+/// How a glyph is resampled when scaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Blocky nearest-neighbour resampling
+    Nearest,
+    /// Scale2x-based smoothing, which rounds corners and fills in diagonal
+    /// steps (falls back to nearest-neighbour when shrinking)
+    Smooth,
+}
+
+impl ScaleMode {
+    /// Parse a scale mode from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "nearest" => Ok(ScaleMode::Nearest),
+            "smooth" => Ok(ScaleMode::Smooth),
+            _ => Err(format!("Unknown scale mode '{}'", name)),
+        }
+    }
+}
+
+/// Scaled length of a side, never less than one cell
+fn scaled_len(len: usize, factor: f64) -> usize {
+    ((len as f64 * factor).round() as usize).max(1)
+}
+
+/// Double a mask with the Scale2x (EPX) pixel-art algorithm
+///
+/// Each cell becomes a 2x2 block whose corners take the colour of matching
+/// edge neighbours, which bridges diagonal steps and rounds outer corners
+/// without ever breaking a stroke. Cells outside the mask count as blank.
+fn scale2x(mask: &Mask, width: usize) -> Mask {
+    let height = mask.len();
+    let at = |r: isize, c: isize| -> bool {
+        if r < 0 || c < 0 {
+            return false;
+        }
+        mask.get(r as usize)
+            .and_then(|row| row.get(c as usize))
+            .copied()
+            .unwrap_or(false)
+    };
+
+    let mut output = vec![vec![false; width * 2]; height * 2];
+
+    for (r, out_pair) in output.chunks_mut(2).enumerate() {
+        for c in 0..width {
+            let (ri, ci) = (r as isize, c as isize);
+            let p = at(ri, ci);
+            let a = at(ri - 1, ci);
+            let b = at(ri, ci + 1);
+            let cl = at(ri, ci - 1);
+            let d = at(ri + 1, ci);
+
+            let top_left = if cl == a && cl != d && a != b { a } else { p };
+            let top_right = if a == b && a != cl && b != d { b } else { p };
+            let bottom_left = if d == cl && d != b && cl != a { cl } else { p };
+            let bottom_right = if b == d && b != a && d != cl { d } else { p };
+
+            out_pair[0][2 * c] = top_left;
+            out_pair[0][2 * c + 1] = top_right;
+            out_pair[1][2 * c] = bottom_left;
+            out_pair[1][2 * c + 1] = bottom_right;
+        }
+    }
+
+    output
+}
+
+/// Nearest-neighbour resample of a mask to exactly `new_width` x `new_height`
+fn resample_nearest(mask: &Mask, width: usize, new_width: usize, new_height: usize) -> Mask {
+    let height = mask.len();
+    (0..new_height)
+        .map(|y| {
+            let r = ((y as f64 + 0.5) * height as f64 / new_height as f64) as usize;
+            (0..new_width)
+                .map(|x| {
+                    let c = ((x as f64 + 0.5) * width as f64 / new_width as f64) as usize;
+                    mask[r.min(height - 1)]
+                        .get(c.min(width - 1))
+                        .copied()
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Scale a glyph mask by `factor`
+///
+/// The result is `round(width * factor)` by `round(height * factor)` cells
+/// (at least one of each). A factor of 1 returns the mask unchanged.
+pub fn scale_mask(mask: &Mask, factor: f64, mode: ScaleMode) -> Mask {
+    let height = mask.len();
+    let width = mask.iter().map(Vec::len).max().unwrap_or(0);
+    if height == 0 || width == 0 || factor == 1.0 {
+        return mask.clone();
+    }
+
+    let (new_width, new_height) = (scaled_len(width, factor), scaled_len(height, factor));
+
+    match mode {
+        ScaleMode::Smooth if factor > 1.0 => {
+            // Double with Scale2x until at least the target size, then
+            // bring it down to the exact size
+            let mut smoothed = mask.clone();
+            let mut smoothed_width = width;
+            while smoothed_width < new_width {
+                smoothed = scale2x(&smoothed, smoothed_width);
+                smoothed_width *= 2;
+            }
+            resample_nearest(&smoothed, smoothed_width, new_width, new_height)
+        }
+        _ => resample_nearest(mask, width, new_width, new_height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(lines: &[&str]) -> Mask {
+        lines
+            .iter()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    fn render(mask: &Mask) -> Vec<String> {
+        mask.iter()
+            .map(|row| row.iter().map(|&on| if on { '#' } else { '.' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_scale_mode() {
+        assert_eq!(ScaleMode::parse("nearest"), Ok(ScaleMode::Nearest));
+        assert_eq!(ScaleMode::parse("Smooth"), Ok(ScaleMode::Smooth));
+        assert!(ScaleMode::parse("cubic").is_err());
+    }
+
+    #[test]
+    fn test_integer_nearest_scale() {
+        let scaled = scale_mask(&mask(&["#.", ".#"]), 2.0, ScaleMode::Nearest);
+        assert_eq!(render(&scaled), vec!["##..", "##..", "..##", "..##"]);
+    }
+
+    #[test]
+    fn test_fractional_scale_dimensions() {
+        let glyph = mask(&["#####", "#...#", "#####", "#...#", "#...#"]);
+
+        let small = scale_mask(&glyph, 0.6, ScaleMode::Nearest);
+        assert_eq!(small.len(), 3);
+        assert!(small.iter().all(|row| row.len() == 3));
+
+        let large = scale_mask(&glyph, 1.5, ScaleMode::Nearest);
+        assert_eq!(large.len(), 8);
+    }
+
+    #[test]
+    fn test_smooth_scale_bridges_diagonals() {
+        let diagonal = mask(&["#.", ".#"]);
+
+        let nearest = scale_mask(&diagonal, 2.0, ScaleMode::Nearest);
+        let smooth = scale_mask(&diagonal, 2.0, ScaleMode::Smooth);
+
+        assert_eq!(render(&nearest), vec!["##..", "##..", "..##", "..##"]);
+        assert_eq!(render(&smooth), vec!["##..", "###.", ".###", "..##"]);
+    }
+
+    #[test]
+    fn test_smooth_scale_keeps_strokes() {
+        let bar = mask(&[".#.", ".#.", ".#."]);
+        let smooth = scale_mask(&bar, 3.0, ScaleMode::Smooth);
+
+        assert_eq!(smooth.len(), 9);
+        // The vertical stroke stays unbroken from top to bottom
+        assert!(smooth.iter().all(|row| row[4]));
+    }
+
+    #[test]
+    fn test_scale_one_is_identity() {
+        let glyph = mask(&["#.#", ".#."]);
+        assert_eq!(scale_mask(&glyph, 1.0, ScaleMode::Smooth), glyph);
+    }
+}
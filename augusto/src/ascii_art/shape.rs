@@ -0,0 +1,363 @@
+//! Picture-shaped word art
+//!
+//! This module turns a grayscale image into a [`Mask`] of "dark" cells that
+//! can be filled with a word just like a glyph. Images are read from PGM
+//! (`P2` or `P5`) or PNG files, downsampled to a target width in characters
+//! (taking into account that terminal cells are taller than they are wide)
+//! and thresholded, optionally with Floyd–Steinberg dithering.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{fill_mask, fill::FillStrategy, shape::{load_image, shape_mask, ShapeOptions}};
+//!
+//! let image = load_image("cat.png").unwrap();
+//! let mask = shape_mask(&image, &ShapeOptions::default());
+//! let canvas = fill_mask(&mask, "miau", FillStrategy::Global);
+//! println!("{}", canvas.to_text());
+//! ```
+
+use std::fs;
+
+use super::fill::Mask;
+
+/// An 8-bit grayscale image, 0 is black and 255 is white
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrayImage {
+    /// Width in pixels
+    pub width: usize,
+    /// Height in pixels
+    pub height: usize,
+    /// Row-major luminance values
+    pub pixels: Vec<u8>,
+}
+
+/// Options controlling how an image becomes a mask
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeOptions {
+    /// Width of the result in characters
+    pub width: usize,
+    /// Height of a character cell relative to its width
+    pub aspect: f64,
+    /// Luminance below which a cell counts as dark
+    pub threshold: u8,
+    /// Diffuse the quantisation error instead of hard thresholding
+    pub dither: bool,
+    /// Fill the light cells instead of the dark ones
+    pub invert: bool,
+}
+
+impl Default for ShapeOptions {
+    fn default() -> Self {
+        Self {
+            width: 60,
+            aspect: 2.0,
+            threshold: 128,
+            dither: false,
+            invert: false,
+        }
+    }
+}
+
+/// Read a PGM or PNG file, detecting the format from its contents
+pub fn load_image(path: &str) -> Result<GrayImage, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Could not read '{}': {}", path, e))?;
+
+    if bytes.starts_with(b"\x89PNG") {
+        decode_png(&bytes)
+    } else if bytes.starts_with(b"P2") || bytes.starts_with(b"P5") {
+        parse_pgm(&bytes)
+    } else {
+        Err(format!(
+            "Unsupported image format in '{}'. Expected PGM or PNG.",
+            path
+        ))
+    }
+}
+
+/// Parse a plain (`P2`) or binary (`P5`) PGM image
+fn parse_pgm(bytes: &[u8]) -> Result<GrayImage, String> {
+    let invalid = |what: &str| format!("Invalid PGM image: {}", what);
+
+    // Header: magic, width, height, maxval, separated by whitespace and comments
+    let mut pos = 0;
+    let mut next_token = || -> Option<String> {
+        loop {
+            while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            if pos < bytes.len() && bytes[pos] == b'#' {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+                continue;
+            }
+            break;
+        }
+        let start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        (pos > start).then(|| String::from_utf8_lossy(&bytes[start..pos]).into_owned())
+    };
+
+    let magic = next_token().ok_or_else(|| invalid("missing header"))?;
+    let mut number = |name: &str| -> Result<usize, String> {
+        next_token()
+            .and_then(|t| t.parse::<usize>().ok())
+            .ok_or_else(|| invalid(&format!("bad {}", name)))
+    };
+    let width = number("width")?;
+    let height = number("height")?;
+    let max = number("maximum value")?;
+    if max == 0 || max > 65535 {
+        return Err(invalid("maximum value must be between 1 and 65535"));
+    }
+
+    let count = width * height;
+    let samples: Vec<usize> = if magic == "P2" {
+        (0..count)
+            .map(|_| number("pixel value"))
+            .collect::<Result<_, _>>()?
+    } else {
+        // A single whitespace byte separates the header from the raster
+        let data = bytes.get(pos + 1..).unwrap_or_default();
+        let sample_size = if max > 255 { 2 } else { 1 };
+        if data.len() < count * sample_size {
+            return Err(invalid("truncated pixel data"));
+        }
+        data.chunks(sample_size)
+            .take(count)
+            .map(|s| s.iter().fold(0, |acc, &b| (acc << 8) | b as usize))
+            .collect()
+    };
+
+    let pixels = samples
+        .into_iter()
+        .map(|v| (v.min(max) * 255 / max) as u8)
+        .collect();
+
+    Ok(GrayImage {
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Decode a PNG image to grayscale, compositing transparency over white
+fn decode_png(bytes: &[u8]) -> Result<GrayImage, String> {
+    let failed = |e: png::DecodingError| format!("PNG decoding failed: {}", e);
+
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(failed)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(failed)?;
+
+    let channels = info.color_type.samples();
+    let pixels = buffer[..info.buffer_size()]
+        .chunks(channels)
+        .map(|px| {
+            let (luma, alpha) = match px {
+                [l] => (*l as f64, 255.0),
+                [l, a] => (*l as f64, *a as f64),
+                [r, g, b] => (luma(*r, *g, *b), 255.0),
+                [r, g, b, a, ..] => (luma(*r, *g, *b), *a as f64),
+                _ => (255.0, 255.0),
+            };
+            let alpha = alpha / 255.0;
+            (luma * alpha + 255.0 * (1.0 - alpha)).round() as u8
+        })
+        .collect();
+
+    Ok(GrayImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
+}
+
+/// Perceptual luminance of an RGB colour
+fn luma(r: u8, g: u8, b: u8) -> f64 {
+    0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64
+}
+
+/// Average luminance of every output cell
+///
+/// The number of rows is chosen so that the picture keeps its proportions
+/// once each cell is drawn `aspect` times taller than wide.
+fn downsample(image: &GrayImage, width: usize, aspect: f64) -> Vec<Vec<f64>> {
+    if image.width == 0 || image.height == 0 || width == 0 {
+        return Vec::new();
+    }
+
+    let rows = ((image.height as f64 * width as f64 / image.width as f64 / aspect).round()
+        as usize)
+        .max(1);
+    let x_step = image.width as f64 / width as f64;
+    let y_step = image.height as f64 / rows as f64;
+
+    (0..rows)
+        .map(|row| {
+            let y0 = (row as f64 * y_step) as usize;
+            let y1 = (((row + 1) as f64 * y_step) as usize).clamp(y0 + 1, image.height);
+            (0..width)
+                .map(|col| {
+                    let x0 = (col as f64 * x_step) as usize;
+                    let x1 = (((col + 1) as f64 * x_step) as usize).clamp(x0 + 1, image.width);
+                    let mut sum = 0.0;
+                    for y in y0..y1 {
+                        for x in x0..x1 {
+                            sum += image.pixels[y * image.width + x] as f64;
+                        }
+                    }
+                    sum / ((y1 - y0) * (x1 - x0)) as f64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Turn an image into a mask of cells to fill
+///
+/// Dark cells (luminance below the threshold) are `true`, or light cells
+/// when `invert` is set. With `dither`, the rounding error of every cell is
+/// spread to its neighbours so mid-tones become a pattern of filled cells.
+pub fn shape_mask(image: &GrayImage, options: &ShapeOptions) -> Mask {
+    let mut levels = downsample(image, options.width, options.aspect);
+    let threshold = options.threshold as f64;
+    let height = levels.len();
+
+    let mut mask = Vec::with_capacity(height);
+    for row in 0..height {
+        let width = levels[row].len();
+        let mut line = Vec::with_capacity(width);
+
+        for col in 0..width {
+            let value = levels[row][col];
+            let dark = value < threshold;
+            line.push(dark != options.invert);
+
+            if options.dither {
+                let error = value - if dark { 0.0 } else { 255.0 };
+                let mut spread = |r: usize, c: usize, weight: f64| {
+                    if let Some(cell) = levels.get_mut(r).and_then(|l| l.get_mut(c)) {
+                        *cell += error * weight;
+                    }
+                };
+                spread(row, col + 1, 7.0 / 16.0);
+                if col > 0 {
+                    spread(row + 1, col - 1, 3.0 / 16.0);
+                }
+                spread(row + 1, col, 5.0 / 16.0);
+                spread(row + 1, col + 1, 1.0 / 16.0);
+            }
+        }
+
+        mask.push(line);
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::raster::{encode_png, Image};
+    use super::*;
+
+    fn gradient(width: usize, height: usize) -> GrayImage {
+        GrayImage {
+            width,
+            height,
+            pixels: (0..width * height)
+                .map(|i| ((i % width) * 255 / (width - 1)) as u8)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_plain_pgm() {
+        let image = parse_pgm(b"P2\n# a comment\n3 2\n15\n0 15 7\n15 0 15\n").unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.pixels, vec![0, 255, 119, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_parse_binary_pgm() {
+        let mut bytes = b"P5 2 2 255\n".to_vec();
+        bytes.extend_from_slice(&[0, 64, 128, 255]);
+        let image = parse_pgm(&bytes).unwrap();
+        assert_eq!(image.pixels, vec![0, 64, 128, 255]);
+
+        assert!(parse_pgm(b"P5 2 2 255\n\x00").is_err());
+        assert!(parse_pgm(b"P2 2 x 255\n").is_err());
+    }
+
+    #[test]
+    fn test_decode_png_composites_alpha() {
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![0, 0, 0, 255, 0, 0, 0, 0],
+        };
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!((decoded.width, decoded.height), (2, 1));
+        assert_eq!(decoded.pixels, vec![0, 255]);
+    }
+
+    #[test]
+    fn test_shape_mask_dimensions_follow_aspect() {
+        let image = gradient(40, 40);
+        let options = ShapeOptions {
+            width: 20,
+            ..ShapeOptions::default()
+        };
+        let mask = shape_mask(&image, &options);
+        assert_eq!(mask.len(), 10);
+        assert!(mask.iter().all(|line| line.len() == 20));
+    }
+
+    #[test]
+    fn test_shape_mask_threshold_and_invert() {
+        let image = gradient(40, 40);
+        let options = ShapeOptions {
+            width: 10,
+            aspect: 1.0,
+            ..ShapeOptions::default()
+        };
+        let mask = shape_mask(&image, &options);
+        // Dark on the left, light on the right
+        assert!(mask[0][0] && !mask[0][9]);
+
+        let inverted = shape_mask(
+            &image,
+            &ShapeOptions {
+                invert: true,
+                ..options
+            },
+        );
+        assert!(!inverted[0][0] && inverted[0][9]);
+    }
+
+    #[test]
+    fn test_dithering_renders_mid_tones() {
+        let image = GrayImage {
+            width: 20,
+            height: 20,
+            pixels: vec![128; 400],
+        };
+        let options = ShapeOptions {
+            width: 20,
+            aspect: 1.0,
+            dither: true,
+            ..ShapeOptions::default()
+        };
+        let filled = shape_mask(&image, &options)
+            .iter()
+            .flatten()
+            .filter(|&&on| on)
+            .count();
+        // Roughly half the cells, where plain thresholding fills none
+        assert!((150..=250).contains(&filled), "filled {}", filled);
+    }
+}
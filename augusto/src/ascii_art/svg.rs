@@ -0,0 +1,164 @@
+//! SVG export for word art
+//!
+//! This module turns a [`Canvas`] into a standalone SVG document, placing
+//! every filler character as its own positioned `<text>` element so the
+//! result scales cleanly for print.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::ascii_art::{word_art_canvas, svg::{render_svg, SvgOptions}};
+//!
+//! let canvas = word_art_canvas("LUXO", "LIXO", 1);
+//! let svg = render_svg(&canvas, &SvgOptions::default());
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use super::canvas::Canvas;
+use super::escape_markup;
+
+/// Styling options for SVG output
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// CSS font family used for every character
+    pub font_family: String,
+    /// Font size in user units
+    pub font_size: f64,
+    /// Fill colour of the characters
+    pub foreground: String,
+    /// Background colour, or `None` for a transparent background
+    pub background: Option<String>,
+    /// Horizontal distance between character centres
+    pub cell_width: f64,
+    /// Vertical distance between character centres
+    pub cell_height: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            font_family: "monospace".to_string(),
+            font_size: 16.0,
+            foreground: "#000000".to_string(),
+            background: Some("#ffffff".to_string()),
+            cell_width: 12.0,
+            cell_height: 18.0,
+        }
+    }
+}
+
+/// Render a canvas as an SVG document
+///
+/// The canvas is `width * cell_width` by `height * cell_height`; each filled
+/// cell becomes a `<text>` element centred in its cell. Blank cells produce
+/// no output. Cells with a styled foreground carry their own `fill`.
+pub fn render_svg(canvas: &Canvas, options: &SvgOptions) -> String {
+    let width = canvas.width() as f64 * options.cell_width;
+    let height = canvas.height() as f64 * options.cell_height;
+
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height
+    ));
+
+    if let Some(background) = &options.background {
+        output.push_str(&format!(
+            "  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+            escape_markup(background)
+        ));
+    }
+
+    output.push_str(&format!(
+        "  <g font-family=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">\n",
+        escape_markup(&options.font_family),
+        options.font_size,
+        escape_markup(&options.foreground)
+    ));
+
+    for (row_idx, row) in canvas.rows().enumerate() {
+        let y = (row_idx as f64 + 0.5) * options.cell_height;
+
+        for (col_idx, cell) in row.iter().enumerate() {
+            if let Some(cell) = cell {
+                let x = (col_idx as f64 + 0.5) * options.cell_width;
+                let fill = cell
+                    .style
+                    .fg
+                    .map(|fg| format!(" fill=\"{}\"", fg.to_rgb().to_hex()))
+                    .unwrap_or_default();
+                output.push_str(&format!(
+                    "    <text x=\"{}\" y=\"{}\"{}>{}</text>\n",
+                    x,
+                    y,
+                    fill,
+                    escape_markup(&cell.ch.to_string())
+                ));
+            }
+        }
+    }
+
+    output.push_str("  </g>\n");
+    output.push_str("</svg>\n");
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::word_art_canvas;
+    use super::*;
+
+    #[test]
+    fn test_svg_dimensions() {
+        let canvas = word_art_canvas("HI", "rust", 1);
+        let svg = render_svg(&canvas, &SvgOptions::default());
+
+        // 11 columns x 12 units, 5 rows x 18 units
+        assert!(svg.contains("width=\"132\" height=\"90\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_one_text_per_filled_cell() {
+        let canvas = word_art_canvas("A", "x", 1);
+        let svg = render_svg(&canvas, &SvgOptions::default());
+
+        let filled = canvas.to_text().chars().filter(|&c| c == 'x').count();
+        assert_eq!(svg.matches("<text ").count(), filled);
+    }
+
+    #[test]
+    fn test_svg_custom_options() {
+        let canvas = word_art_canvas("I", "o", 1);
+        let options = SvgOptions {
+            font_family: "Courier New".to_string(),
+            font_size: 24.0,
+            foreground: "red".to_string(),
+            background: None,
+            cell_width: 20.0,
+            cell_height: 30.0,
+        };
+        let svg = render_svg(&canvas, &options);
+
+        assert!(svg.contains("font-family=\"Courier New\""));
+        assert!(svg.contains("font-size=\"24\""));
+        assert!(svg.contains("fill=\"red\""));
+        assert!(!svg.contains("<rect"));
+        // First cell of "I" is centred in the first 20x30 cell
+        assert!(svg.contains("<text x=\"10\" y=\"15\">o</text>"));
+    }
+
+    #[test]
+    fn test_svg_escapes_special_characters() {
+        let canvas = word_art_canvas("I", "<&>", 1);
+        let svg = render_svg(&canvas, &SvgOptions::default());
+
+        assert!(svg.contains("&lt;"));
+        assert!(svg.contains("&amp;"));
+        assert!(svg.contains("&gt;"));
+        assert!(!svg.contains("><</text>"));
+    }
+}
@@ -6,9 +6,9 @@
 //! # Examples
 //!
 //! ```
-//! use augusto::benchmark::benchmark_operation;
+//! use augusto::benchmark::{benchmark_operation, BenchmarkConfig};
 //!
-//! let stats = benchmark_operation("anagram", "test", || {
+//! let stats = benchmark_operation("anagram", "test", &BenchmarkConfig::default(), || {
 //!     // Your operation here
 //! });
 //! println!("{}", stats);
@@ -16,6 +16,17 @@
 
 use std::time::{Duration, Instant};
 
+use alloc::AllocStats;
+use stats::Summary;
+
+pub mod alloc;
+pub mod baseline;
+pub mod chart;
+pub mod report;
+pub mod scaling;
+pub mod stats;
+pub mod suite;
+
 /// Performance statistics for an operation
 #[derive(Debug, Clone)]
 pub struct BenchmarkStats {
@@ -31,13 +42,24 @@ pub struct BenchmarkStats {
     pub avg_duration: Duration,
     /// Output size (if applicable)
     pub output_size: Option<usize>,
+    /// Time taken by each iteration, in run order
+    pub samples: Vec<Duration>,
+    /// Distribution of the samples, if any were recorded
+    pub summary: Option<Summary>,
+    /// Memory allocated per iteration, if it was tracked
+    pub memory: Option<AllocStats>,
 }
 
 impl BenchmarkStats {
     /// Create a new BenchmarkStats instance
     pub fn new(operation: String, input: String, duration: Duration, iterations: usize) -> Self {
+        // Divided in nanoseconds, as the iteration count may not fit a u32
         let avg_duration = if iterations > 0 {
-            duration / iterations as u32
+            let nanos = duration.as_nanos() / iterations as u128;
+            Duration::new(
+                (nanos / 1_000_000_000) as u64,
+                (nanos % 1_000_000_000) as u32,
+            )
         } else {
             duration
         };
@@ -49,9 +71,25 @@ impl BenchmarkStats {
             iterations,
             avg_duration,
             output_size: None,
+            samples: Vec::new(),
+            summary: None,
+            memory: None,
         }
     }
 
+    /// Attach per-iteration timings and summarise them
+    pub fn with_samples(mut self, samples: Vec<Duration>) -> Self {
+        self.summary = Summary::from_samples(&samples);
+        self.samples = samples;
+        self
+    }
+
+    /// Set the memory allocated per iteration
+    pub fn with_memory(mut self, memory: AllocStats) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
     /// Set the output size
     pub fn with_output_size(mut self, size: usize) -> Self {
         self.output_size = Some(size);
@@ -61,7 +99,9 @@ impl BenchmarkStats {
     /// Format duration in human-readable form
     fn format_duration(duration: Duration) -> String {
         let micros = duration.as_micros();
-        if micros < 1000 {
+        if micros == 0 {
+            format!("{}ns", duration.as_nanos())
+        } else if micros < 1000 {
             format!("{}μs", micros)
         } else if micros < 1_000_000 {
             format!("{:.2}ms", micros as f64 / 1000.0)
@@ -82,7 +122,7 @@ impl BenchmarkStats {
         output.push_str(&format!("Input:            \"{}\"\n", self.input));
         output.push_str(&format!(
             "Input length:     {} character(s)\n",
-            self.input.len()
+            self.input.chars().count()
         ));
 
         if let Some(size) = self.output_size {
@@ -106,11 +146,94 @@ impl BenchmarkStats {
             output.push_str(&format!("Throughput:       {} ops/sec\n", ops_per_sec));
         }
 
+        if let Some(summary) = &self.summary {
+            output.push('\n');
+            output.push_str(&Self::format_summary(summary, self.samples.len()));
+        }
+
+        if let Some(memory) = &self.memory {
+            output.push('\n');
+            output.push_str(&format!(
+                "Allocated:        {} per run\n",
+                alloc::format_bytes(memory.bytes)
+            ));
+            output.push_str(&format!(
+                "Allocations:      {} per run\n",
+                memory.allocations
+            ));
+            output.push_str(&format!(
+                "Peak live:        {}\n",
+                alloc::format_bytes(memory.peak)
+            ));
+        }
+
         output.push('\n');
         output.push_str("╚════════════════════════════════════════════════════════════╝\n");
 
         output
     }
+
+    /// Format the sample distribution section of the report
+    fn format_summary(summary: &Summary, count: usize) -> String {
+        let mut output = String::new();
+        let d = Self::format_duration;
+
+        output.push_str(&format!(
+            "Min / Max:        {} / {}\n",
+            d(summary.min),
+            d(summary.max)
+        ));
+        output.push_str(&format!("Median:           {}\n", d(summary.median)));
+        output.push_str(&format!(
+            "Mean ± std dev:   {} ± {}\n",
+            d(summary.mean),
+            d(summary.std_dev)
+        ));
+        output.push_str(&format!(
+            "95% CI of mean:   [{}, {}]\n",
+            d(summary.ci_low),
+            d(summary.ci_high)
+        ));
+        output.push_str(&format!(
+            "p95 / p99:        {} / {}\n",
+            d(summary.p95),
+            d(summary.p99)
+        ));
+
+        let outliers = summary.outliers;
+        output.push_str(&format!(
+            "Outliers:         {} of {} ({:.1}%)\n",
+            outliers.total(),
+            count,
+            outliers.total() as f64 * 100.0 / count.max(1) as f64
+        ));
+        for (amount, kind) in [
+            (outliers.low_severe, "low severe"),
+            (outliers.low_mild, "low mild"),
+            (outliers.high_mild, "high mild"),
+            (outliers.high_severe, "high severe"),
+        ] {
+            if amount > 0 {
+                output.push_str(&format!("                  {} {}\n", amount, kind));
+            }
+        }
+
+        output
+    }
+
+    /// Histogram of the sample times, `width` columns wide
+    ///
+    /// Empty if no samples were recorded.
+    pub fn format_histogram(&self, width: usize) -> String {
+        if self.samples.is_empty() {
+            return String::new();
+        }
+        format!(
+            "\nSample times ({} samples):\n{}",
+            self.samples.len(),
+            chart::histogram(&self.samples, width)
+        )
+    }
 }
 
 impl std::fmt::Display for BenchmarkStats {
@@ -119,93 +242,220 @@ impl std::fmt::Display for BenchmarkStats {
     }
 }
 
+/// Upper bound on the number of samples kept for one benchmark
+///
+/// Faster operations are timed in batches so that the measurement still
+/// fills the time budget without storing millions of samples.
+const MAX_SAMPLES: usize = 10_000;
+
+/// How long and how often an operation is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkConfig {
+    /// Time spent running the operation before measuring
+    pub warm_up: Duration,
+    /// Target total time of the measured runs
+    pub measurement_time: Duration,
+    /// Fewest samples taken, even if they exceed the time budget
+    pub min_samples: usize,
+    /// Also run the operation under the counting allocator
+    pub track_memory: bool,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            warm_up: Duration::from_millis(500),
+            measurement_time: Duration::from_secs(2),
+            min_samples: 10,
+            track_memory: false,
+        }
+    }
+}
+
+/// Number of extra runs made to measure memory
+const MEMORY_RUNS: u64 = 3;
+
 /// Benchmark a simple operation
 ///
-/// Runs the operation multiple times and returns statistics
+/// Warms the operation up, estimates its cost and runs it as many times as
+/// fit in the configured measurement time (but at least `min_samples` times)
 ///
 /// # Arguments
 ///
 /// * `operation` - Name of the operation
 /// * `input` - Input string
+/// * `config` - Warm-up, time budget and minimum sample count
 /// * `f` - Function to benchmark
 ///
 /// # Returns
 ///
 /// BenchmarkStats with performance information
-pub fn benchmark_operation<F, T>(operation: &str, input: &str, mut f: F) -> BenchmarkStats
+pub fn benchmark_operation<F, T>(
+    operation: &str,
+    input: &str,
+    config: &BenchmarkConfig,
+    mut f: F,
+) -> BenchmarkStats
 where
     F: FnMut() -> T,
 {
-    // Warm-up run
-    let _ = f();
-
-    // Determine number of iterations based on expected complexity
-    let iterations = calculate_iterations(input);
-
-    let start = Instant::now();
-    for _ in 0..iterations {
-        let _ = f();
-    }
-    let duration = start.elapsed();
-
-    BenchmarkStats::new(
-        operation.to_string(),
-        input.to_string(),
-        duration,
-        iterations,
-    )
+    let per_call = warm_up(config.warm_up, &mut f, |_| ());
+    measure(operation, input, config, per_call, &mut f)
 }
 
 /// Benchmark an operation that returns a result
 ///
 /// Similar to benchmark_operation but captures the output size
-pub fn benchmark_with_result<F, T>(operation: &str, input: &str, mut f: F) -> BenchmarkStats
+pub fn benchmark_with_result<F, T>(
+    operation: &str,
+    input: &str,
+    config: &BenchmarkConfig,
+    mut f: F,
+) -> BenchmarkStats
 where
     F: FnMut() -> T,
     T: IntoIterator,
     T::Item: Sized,
 {
-    // Warm-up and get output size
-    let result = f();
-    let output_size = result.into_iter().count();
+    // The output size is taken from the first warm-up run
+    let mut output_size = None;
+    let per_call = warm_up(config.warm_up, &mut f, |result| {
+        output_size.get_or_insert_with(|| result.into_iter().count());
+    });
 
-    // Determine number of iterations
-    let iterations = calculate_iterations(input);
+    measure(operation, input, config, per_call, &mut f).with_output_size(output_size.unwrap_or(0))
+}
 
+/// Run `f` for at least `duration` (and at least once); returns the mean time per call
+fn warm_up<F, T>(duration: Duration, f: &mut F, mut inspect: impl FnMut(T)) -> Duration
+where
+    F: FnMut() -> T,
+{
     let start = Instant::now();
-    for _ in 0..iterations {
-        let _ = f();
+    let mut calls = 0u32;
+    loop {
+        inspect(f());
+        calls += 1;
+        if start.elapsed() >= duration || calls == u32::MAX {
+            break;
+        }
     }
-    let duration = start.elapsed();
+    start.elapsed() / calls
+}
 
-    BenchmarkStats::new(
+/// Run the measured samples and build the stats
+fn measure<F, T>(
+    operation: &str,
+    input: &str,
+    config: &BenchmarkConfig,
+    per_call: Duration,
+    f: &mut F,
+) -> BenchmarkStats
+where
+    F: FnMut() -> T,
+{
+    let (samples, batch) = plan_samples(config, per_call);
+    let calls_per_sample = u32::try_from(batch).unwrap_or(u32::MAX);
+    let mut timings = Vec::with_capacity(samples.min(MAX_SAMPLES));
+    let start = Instant::now();
+    let time_limit = config.measurement_time.saturating_mul(2);
+
+    for taken in 0..samples {
+        // Stop early if the estimate was too optimistic
+        if taken >= config.min_samples && start.elapsed() >= time_limit {
+            break;
+        }
+        let sample_start = Instant::now();
+        for _ in 0..batch {
+            let result = f();
+            // Keep the compiler from discarding the work
+            std::hint::black_box(result);
+        }
+        timings.push(sample_start.elapsed() / calls_per_sample);
+    }
+
+    let iterations = timings.len() * batch;
+    let duration = timings
+        .iter()
+        .sum::<Duration>()
+        .saturating_mul(calls_per_sample);
+    let stats = BenchmarkStats::new(
         operation.to_string(),
         input.to_string(),
         duration,
         iterations,
     )
-    .with_output_size(output_size)
+    .with_samples(timings);
+
+    if config.track_memory {
+        stats.with_memory(measure_memory(f))
+    } else {
+        stats
+    }
 }
 
-/// Calculate appropriate number of iterations based on input
-fn calculate_iterations(input: &str) -> usize {
-    let len = input.len();
-
-    // For very short inputs, do more iterations
-    if len <= 3 {
-        10000
-    } else if len <= 5 {
-        1000
-    } else if len <= 7 {
-        100
-    } else if len <= 9 {
-        10
+/// Memory per run, averaged over a few untimed runs (peak is the largest)
+fn measure_memory<F, T>(f: &mut F) -> AllocStats
+where
+    F: FnMut() -> T,
+{
+    let mut total = AllocStats::default();
+    for _ in 0..MEMORY_RUNS {
+        let (result, run) = alloc::track(&mut *f);
+        drop(result);
+        total.bytes += run.bytes;
+        total.allocations += run.allocations;
+        total.peak = total.peak.max(run.peak);
+    }
+
+    AllocStats {
+        bytes: total.bytes / MEMORY_RUNS,
+        allocations: total.allocations / MEMORY_RUNS,
+        peak: total.peak,
+    }
+}
+
+/// Number of samples and calls per sample for an operation taking `per_call`
+///
+/// Fills the measurement time, takes at least `min_samples` samples and
+/// groups calls into batches once more than [`MAX_SAMPLES`] would be needed.
+/// A batch holds at most `u32::MAX` calls, so its time can be divided by it.
+fn plan_samples(config: &BenchmarkConfig, per_call: Duration) -> (usize, usize) {
+    let per_call = per_call.as_nanos().max(1);
+    let calls = (config.measurement_time.as_nanos() / per_call).min(usize::MAX as u128) as usize;
+    let min_samples = config.min_samples.max(1);
+
+    if calls <= MAX_SAMPLES {
+        (calls.max(min_samples), 1)
     } else {
-        // For very long inputs, just do a few runs
-        1
+        let batch = calls.div_ceil(MAX_SAMPLES).min(u32::MAX as usize);
+        ((calls / batch).max(min_samples), batch)
     }
 }
 
+/// Parse a duration such as `3s`, `500ms`, `1.5s`, `250us` or `2m`
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let invalid = || format!("Invalid duration '{}'. Expected e.g. 3s or 500ms.", text);
+    let seconds_per_unit = match unit.trim() {
+        "" | "s" | "sec" | "secs" => 1.0,
+        "ms" => 1e-3,
+        "us" | "μs" => 1e-6,
+        "ns" => 1e-9,
+        "m" | "min" => 60.0,
+        _ => return Err(invalid()),
+    };
+
+    // Durations too long to represent are rejected rather than panicking
+    let value = number.parse::<f64>().map_err(|_| invalid())?;
+    Duration::try_from_secs_f64(value * seconds_per_unit).map_err(|_| invalid())
+}
+
 /// Benchmark multiple operations and compare them
 pub struct BenchmarkSuite {
     results: Vec<BenchmarkStats>,
@@ -224,6 +474,11 @@ impl BenchmarkSuite {
         self.results.push(stats);
     }
 
+    /// Results in the order they were added
+    pub fn results(&self) -> &[BenchmarkStats] {
+        &self.results
+    }
+
     /// Format comparison results
     pub fn format_comparison(&self) -> String {
         if self.results.is_empty() {
@@ -248,6 +503,14 @@ impl BenchmarkSuite {
                 BenchmarkStats::format_duration(stat.duration),
                 BenchmarkStats::format_duration(stat.avg_duration)
             ));
+            if let Some(summary) = &stat.summary {
+                output.push_str(&format!(
+                    "   Median: {} (± {}, p99 {})\n",
+                    BenchmarkStats::format_duration(summary.median),
+                    BenchmarkStats::format_duration(summary.std_dev),
+                    BenchmarkStats::format_duration(summary.p99)
+                ));
+            }
             if let Some(size) = stat.output_size {
                 output.push_str(&format!("   Output: {} items\n", size));
             }
@@ -256,6 +519,30 @@ impl BenchmarkSuite {
 
         output
     }
+
+    /// Bar chart of the mean time per run of each result, `width` columns wide
+    ///
+    /// Bars are labelled with the input, and also the operation when the
+    /// suite mixes operations.
+    pub fn format_chart(&self, width: usize) -> String {
+        let mixed = self
+            .results
+            .windows(2)
+            .any(|pair| pair[0].operation != pair[1].operation);
+        let entries: Vec<(String, Duration)> = self
+            .results
+            .iter()
+            .map(|stat| {
+                let label = if mixed {
+                    format!("{} \"{}\"", stat.operation, stat.input)
+                } else {
+                    stat.input.clone()
+                };
+                (label, stat.avg_duration)
+            })
+            .collect();
+        chart::bar_chart(&entries, width)
+    }
 }
 
 impl Default for BenchmarkSuite {
@@ -281,6 +568,22 @@ mod tests {
         assert_eq!(stats.input, "input");
         assert_eq!(stats.iterations, 10);
         assert!(stats.duration.as_millis() == 100);
+        assert_eq!(stats.avg_duration, Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_average_with_many_iterations() {
+        let iterations = u32::MAX as usize + 1;
+        let stats = BenchmarkStats::new(
+            "op".to_string(),
+            "in".to_string(),
+            Duration::from_nanos(3 * iterations as u64),
+            iterations,
+        );
+        assert_eq!(stats.avg_duration, Duration::from_nanos(3));
+
+        let stats = BenchmarkStats::new("op".to_string(), "ação".to_string(), Duration::ZERO, 1);
+        assert!(stats.format().contains("Input length:     4 character(s)"));
     }
 
     #[test]
@@ -296,29 +599,136 @@ mod tests {
         assert_eq!(stats.output_size, Some(42));
     }
 
+    /// A short budget so the tests stay fast
+    fn quick_config() -> BenchmarkConfig {
+        BenchmarkConfig {
+            warm_up: Duration::from_millis(1),
+            measurement_time: Duration::from_millis(20),
+            min_samples: 5,
+            track_memory: false,
+        }
+    }
+
     #[test]
-    fn test_calculate_iterations() {
-        assert_eq!(calculate_iterations("ab"), 10000);
-        assert_eq!(calculate_iterations("abcd"), 1000);
-        assert_eq!(calculate_iterations("abcdef"), 100);
-        assert_eq!(calculate_iterations("abcdefgh"), 10);
-        assert_eq!(calculate_iterations("abcdefghijk"), 1);
+    fn test_plan_samples() {
+        let config = BenchmarkConfig {
+            warm_up: Duration::ZERO,
+            measurement_time: Duration::from_secs(3),
+            min_samples: 10,
+            track_memory: false,
+        };
+
+        // Slow operations still get the minimum number of samples
+        assert_eq!(plan_samples(&config, Duration::from_secs(1)), (10, 1));
+        assert_eq!(plan_samples(&config, Duration::from_millis(1)), (3000, 1));
+        // Fast ones are batched to fill the budget
+        assert_eq!(
+            plan_samples(&config, Duration::from_nanos(100)),
+            (MAX_SAMPLES, 3000)
+        );
+        assert_eq!(plan_samples(&config, Duration::ZERO).0, MAX_SAMPLES);
+
+        // Huge budgets cap the batch instead of overflowing it
+        let huge = BenchmarkConfig {
+            measurement_time: Duration::MAX,
+            ..config
+        };
+        assert_eq!(
+            plan_samples(&huge, Duration::from_nanos(1)).1,
+            u32::MAX as usize
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("3s"), Ok(Duration::from_secs(3)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250us"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("3 hours").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("99999999999999999999999s").is_err());
     }
 
     #[test]
     fn test_benchmark_operation() {
-        let stats = benchmark_operation("test", "abc", || {
+        let stats = benchmark_operation("test", "abc", &quick_config(), || {
             // Simple operation
             let _ = "abc".len();
         });
 
         assert_eq!(stats.operation, "test");
         assert_eq!(stats.input, "abc");
-        assert!(stats.iterations > 0);
+        assert!(stats.samples.len() >= 5);
+        assert_eq!(stats.iterations % stats.samples.len(), 0);
+        assert!(stats.summary.is_some());
+    }
+
+    #[test]
+    fn test_benchmark_with_result_meets_minimum_samples() {
+        let config = BenchmarkConfig {
+            measurement_time: Duration::ZERO,
+            ..quick_config()
+        };
+        let stats = benchmark_with_result("test", "abc", &config, || vec![1, 2, 3]);
+
+        assert_eq!(stats.output_size, Some(3));
+        assert_eq!(stats.samples.len(), 5);
+        assert_eq!(stats.iterations, 5);
+        assert_eq!(stats.memory, None);
+    }
+
+    #[test]
+    fn test_benchmark_tracks_memory() {
+        let config = BenchmarkConfig {
+            track_memory: true,
+            ..quick_config()
+        };
+        let stats = benchmark_operation("test", "abc", &config, || vec![0u64; 128]);
+
+        let memory = stats.memory.expect("memory was tracked");
+        assert_eq!(memory.allocations, 1);
+        assert_eq!(memory.bytes, 1024);
+        assert_eq!(memory.peak, 1024);
+
+        let report = stats.format();
+        assert!(report.contains("Allocated:        1.00 KiB per run"));
+        assert!(report.contains("Allocations:      1 per run"));
+        assert!(report.contains("Peak live:        1.00 KiB"));
+    }
+
+    #[test]
+    fn test_format_includes_sample_statistics() {
+        let samples = [10, 11, 12, 10, 11, 90]
+            .iter()
+            .map(|&us| Duration::from_micros(us))
+            .collect();
+        let total = Duration::from_micros(144);
+        let stats =
+            BenchmarkStats::new("op".to_string(), "in".to_string(), total, 6).with_samples(samples);
+
+        assert_eq!(stats.iterations, 6);
+        assert_eq!(stats.avg_duration, Duration::from_micros(24));
+        let report = stats.format();
+        assert!(report.contains("Median:           11μs"));
+        assert!(report.contains("Min / Max:        10μs / 90μs"));
+        assert!(report.contains("Outliers:         1 of 6 (16.7%)"));
+        assert!(report.contains("1 high severe"));
+        assert!(report.contains("95% CI of mean:"));
+        assert!(report.contains("p95 / p99:"));
+
+        let plain = BenchmarkStats::new("op".to_string(), "in".to_string(), Duration::ZERO, 0);
+        assert!(!plain.format().contains("Median"));
     }
 
     #[test]
     fn test_format_duration() {
+        assert_eq!(
+            BenchmarkStats::format_duration(Duration::from_nanos(250)),
+            "250ns"
+        );
         assert_eq!(
             BenchmarkStats::format_duration(Duration::from_micros(500)),
             "500μs"
@@ -350,4 +760,38 @@ mod tests {
         assert!(comparison.contains("op1"));
         assert!(comparison.contains("test"));
     }
+
+    #[test]
+    fn test_suite_chart_and_histogram() {
+        let mut suite = BenchmarkSuite::new();
+        for (input, millis) in [("cat", 10), ("test", 40)] {
+            suite.add(BenchmarkStats::new(
+                "op1".to_string(),
+                input.to_string(),
+                Duration::from_millis(millis),
+                100,
+            ));
+        }
+
+        let chart = suite.format_chart(50);
+        assert!(chart.starts_with("cat  │"), "{}", chart);
+        assert!(chart.lines().nth(1).unwrap().ends_with(" 400μs"));
+
+        suite.add(BenchmarkStats::new(
+            "op2".to_string(),
+            "cat".to_string(),
+            Duration::from_millis(1),
+            1,
+        ));
+        assert!(suite.format_chart(50).contains("op2 \"cat\""));
+
+        let stats = &suite.results()[0];
+        assert_eq!(stats.format_histogram(50), "");
+        let sampled = stats
+            .clone()
+            .with_samples(vec![Duration::from_micros(100); 4]);
+        assert!(sampled
+            .format_histogram(50)
+            .starts_with("\nSample times (4 samples):\n"));
+    }
 }
@@ -0,0 +1,181 @@
+//! Allocation tracking for benchmarks
+//!
+//! [`CountingAllocator`] wraps the system allocator and, while [`track`] is
+//! running on the current thread, counts the bytes and allocations requested
+//! and the peak of live bytes. Counting is per thread, so allocations made by
+//! other threads (such as parallel tests) are not included. Outside
+//! [`track`] the only overhead is a thread-local flag check.
+//!
+//! The allocator is installed as the global allocator in `main.rs`.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::benchmark::alloc::track;
+//!
+//! let (_, memory) = track(|| vec![0u8; 1024]);
+//! assert!(memory.bytes >= 1024);
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Memory used by a piece of code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Total bytes requested, including reallocations
+    pub bytes: u64,
+    /// Number of allocations and reallocations
+    pub allocations: u64,
+    /// Most bytes live at once, counting only memory allocated while tracking
+    pub peak: u64,
+}
+
+/// Running totals for the current thread
+#[derive(Clone, Copy)]
+struct Counters {
+    bytes: u64,
+    allocations: u64,
+    live: i64,
+    peak: i64,
+}
+
+impl Counters {
+    const ZERO: Counters = Counters {
+        bytes: 0,
+        allocations: 0,
+        live: 0,
+        peak: 0,
+    };
+}
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static COUNTERS: Cell<Counters> = const { Cell::new(Counters::ZERO) };
+}
+
+/// Update the counters of the current thread if it is being tracked
+fn record(allocated: usize, freed: usize) {
+    // `try_with` because the allocator may run while thread-locals are torn down
+    let tracking = TRACKING.try_with(Cell::get).unwrap_or(false);
+    if !tracking {
+        return;
+    }
+    let _ = COUNTERS.try_with(|counters| {
+        let mut c = counters.get();
+        if allocated > 0 {
+            c.bytes += allocated as u64;
+            c.allocations += 1;
+        }
+        c.live += allocated as i64 - freed as i64;
+        c.peak = c.peak.max(c.live);
+        counters.set(c);
+    });
+}
+
+/// The system allocator with per-thread allocation counting
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Run `f` and report the memory it allocated on this thread
+///
+/// The result is returned rather than dropped, so freeing it is not counted.
+pub fn track<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    COUNTERS.with(|counters| counters.set(Counters::ZERO));
+    TRACKING.with(|tracking| tracking.set(true));
+    let result = f();
+    TRACKING.with(|tracking| tracking.set(false));
+
+    let c = COUNTERS.with(Cell::get);
+    let stats = AllocStats {
+        bytes: c.bytes,
+        allocations: c.allocations,
+        peak: c.peak.max(0) as u64,
+    };
+    (result, stats)
+}
+
+/// Bytes in a human-readable unit
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_counts_allocations() {
+        let (vectors, stats) = track(|| {
+            let first = vec![0u8; 1000];
+            let second = vec![0u8; 500];
+            drop(first);
+            vec![second, vec![0u8; 200]]
+        });
+
+        assert_eq!(vectors.len(), 2);
+        assert!(stats.allocations >= 4, "{:?}", stats);
+        assert!(stats.bytes >= 1700, "{:?}", stats);
+        // The first vector is freed before the last one is allocated
+        assert!(
+            stats.peak >= 1500 && stats.peak < 1700 + 1000,
+            "{:?}",
+            stats
+        );
+    }
+
+    #[test]
+    fn test_track_ignores_untracked_code() {
+        let outside = vec![1u8; 4096];
+        let (_, stats) = track(|| 1 + 1);
+        assert_eq!(stats, AllocStats::default());
+        drop(outside);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.00 MiB");
+    }
+}
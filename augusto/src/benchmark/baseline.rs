@@ -0,0 +1,521 @@
+//! Saved benchmark baselines and regression checks
+//!
+//! A baseline is a named set of results saved as a JSON report (the format
+//! of [`report::to_json`](super::report::to_json)) in the baseline
+//! directory: `$AUGUSTO_BASELINE_DIR` if set, otherwise `.augusto/baselines`
+//! under the current directory. Saving merges into an existing baseline,
+//! replacing results for the same operation and input.
+//!
+//! New results are compared with the baseline result for the same operation
+//! and input. The change is the difference of the means, and Welch's t-test
+//! (with a normal approximation, as samples are plentiful) tells whether it
+//! is statistically significant. A significant slowdown beyond the
+//! threshold is a regression. [`format_trends`] draws how each result
+//! moved across every baseline saved in the directory.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::benchmark::{baseline, benchmark_operation, BenchmarkConfig};
+//!
+//! let results = [benchmark_operation("len", "abc", &BenchmarkConfig::default(), || "abc".len())];
+//! let saved = baseline::load(&baseline::baseline_dir(), "main").unwrap();
+//! for comparison in baseline::compare(&results, &saved) {
+//!     println!("{}: {:+.1}%", comparison.operation, comparison.change);
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::report::{to_json, Environment};
+use super::BenchmarkStats;
+
+/// Directory used when `AUGUSTO_BASELINE_DIR` is not set
+const DEFAULT_DIR: &str = ".augusto/baselines";
+
+/// p-value below which a change counts as significant
+const SIGNIFICANCE: f64 = 0.05;
+
+/// A saved result: enough to compare a new run with
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineEntry {
+    /// Name of the operation
+    pub operation: String,
+    /// Input the operation ran on
+    pub input: String,
+    /// Mean time per iteration
+    pub mean: Duration,
+    /// Standard deviation of the samples
+    pub std_dev: Duration,
+    /// Number of samples
+    pub samples: usize,
+}
+
+/// A new result compared with its baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Name of the operation
+    pub operation: String,
+    /// Input the operation ran on
+    pub input: String,
+    /// Mean time per iteration in the baseline
+    pub baseline_mean: Duration,
+    /// Mean time per iteration now
+    pub current_mean: Duration,
+    /// Change of the mean in percent; positive is slower
+    pub change: f64,
+    /// Two-sided p-value of Welch's t-test
+    pub p_value: f64,
+}
+
+impl Comparison {
+    /// Whether the change is unlikely to be noise
+    pub fn is_significant(&self) -> bool {
+        self.p_value < SIGNIFICANCE
+    }
+
+    /// Whether this is a significant slowdown of more than `threshold` percent
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.is_significant() && self.change > threshold
+    }
+}
+
+/// Directory baselines are stored in
+pub fn baseline_dir() -> PathBuf {
+    std::env::var_os("AUGUSTO_BASELINE_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_DIR))
+}
+
+/// Path of the baseline called `name` in `dir`
+fn baseline_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.');
+    if !valid {
+        return Err(format!(
+            "Invalid baseline name '{}'. Use letters, digits, '-', '_' and '.'.",
+            name
+        ));
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Operation and input of a result in a JSON report
+fn result_key(result: &Value) -> (Option<&str>, Option<&str>) {
+    (result["operation"].as_str(), result["input"].as_str())
+}
+
+/// Save results as the baseline `name` in `dir`, merging with what is already there
+///
+/// Returns the path written.
+pub fn save(
+    dir: &Path,
+    name: &str,
+    results: &[BenchmarkStats],
+    environment: &Environment,
+) -> Result<PathBuf, String> {
+    let path = baseline_path(dir, name)?;
+    let mut document: Value = serde_json::from_str(&to_json(results, environment))
+        .map_err(|e| format!("Could not serialise results: {}", e))?;
+
+    if let Ok(existing) = fs::read_to_string(&path) {
+        let existing: Value = serde_json::from_str(&existing)
+            .map_err(|e| format!("Invalid baseline '{}': {}", path.display(), e))?;
+        let new_results = document["results"].as_array().cloned().unwrap_or_default();
+        let mut merged: Vec<Value> = existing["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|old| {
+                !new_results
+                    .iter()
+                    .any(|new| result_key(new) == result_key(old))
+            })
+            .cloned()
+            .collect();
+        merged.extend(new_results);
+        document["results"] = Value::Array(merged);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create '{}': {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(&document).unwrap_or_default() + "\n";
+    fs::write(&path, text).map_err(|e| format!("Could not write '{}': {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Load the baseline `name` from `dir`
+pub fn load(dir: &Path, name: &str) -> Result<Vec<BaselineEntry>, String> {
+    let path = baseline_path(dir, name)?;
+    let text = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "Could not read baseline '{}' ({}): {}",
+            name,
+            path.display(),
+            e
+        )
+    })?;
+    parse(&text).map_err(|e| format!("Invalid baseline '{}': {}", path.display(), e))
+}
+
+/// Every baseline saved in `dir`, oldest first by when it was last saved
+///
+/// Files that cannot be read or parsed are skipped, and a missing directory
+/// has no baselines.
+pub fn history(dir: &Path) -> Vec<Vec<BaselineEntry>> {
+    let mut baselines: Vec<(String, Vec<BaselineEntry>)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|file| file.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let document: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+            let timestamp = document["environment"]["timestamp"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            Some((timestamp, entries(&document).ok()?))
+        })
+        .collect();
+    // ISO 8601 timestamps in UTC sort chronologically as text
+    baselines.sort_by(|a, b| a.0.cmp(&b.0));
+    baselines.into_iter().map(|(_, entries)| entries).collect()
+}
+
+/// Read the entries of a JSON report, skipping results without statistics
+fn parse(text: &str) -> Result<Vec<BaselineEntry>, String> {
+    let document: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    entries(&document)
+}
+
+/// Entries of a parsed JSON report
+fn entries(document: &Value) -> Result<Vec<BaselineEntry>, String> {
+    let results = document["results"]
+        .as_array()
+        .ok_or_else(|| "missing 'results' array".to_string())?;
+
+    Ok(results
+        .iter()
+        .filter_map(|result| {
+            let statistics = &result["statistics"];
+            Some(BaselineEntry {
+                operation: result["operation"].as_str()?.to_string(),
+                input: result["input"].as_str()?.to_string(),
+                mean: Duration::from_nanos(statistics["mean_ns"].as_u64()?),
+                std_dev: Duration::from_nanos(statistics["std_dev_ns"].as_u64()?),
+                samples: result["samples"].as_u64()? as usize,
+            })
+        })
+        .collect())
+}
+
+/// Compare results with the baseline entries for the same operation and input
+///
+/// Results without samples or without a matching entry are left out.
+pub fn compare(results: &[BenchmarkStats], baseline: &[BaselineEntry]) -> Vec<Comparison> {
+    results
+        .iter()
+        .filter_map(|stats| {
+            let summary = stats.summary.as_ref()?;
+            let entry = baseline
+                .iter()
+                .find(|e| e.operation == stats.operation && e.input == stats.input)?;
+
+            let old = entry.mean.as_secs_f64();
+            let new = summary.mean.as_secs_f64();
+            let change = if old > 0.0 {
+                (new - old) / old * 100.0
+            } else {
+                0.0
+            };
+
+            Some(Comparison {
+                operation: stats.operation.clone(),
+                input: stats.input.clone(),
+                baseline_mean: entry.mean,
+                current_mean: summary.mean,
+                change,
+                p_value: welch_p_value(
+                    (old, entry.std_dev.as_secs_f64(), entry.samples),
+                    (new, summary.std_dev.as_secs_f64(), stats.samples.len()),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Two-sided p-value of Welch's t-test from `(mean, std_dev, count)` pairs
+///
+/// Uses the normal distribution in place of Student's t, which is close
+/// enough for the hundreds of samples a benchmark takes.
+fn welch_p_value(a: (f64, f64, usize), b: (f64, f64, usize)) -> f64 {
+    let variance = |(_, sd, n): (f64, f64, usize)| sd * sd / n.max(1) as f64;
+    let error = (variance(a) + variance(b)).sqrt();
+
+    if error == 0.0 {
+        return if a.0 == b.0 { 1.0 } else { 0.0 };
+    }
+    let t = (a.0 - b.0).abs() / error;
+    (1.0 - erf(t / std::f64::consts::SQRT_2)).clamp(0.0, 1.0)
+}
+
+/// Error function (Abramowitz and Stegun 7.1.26, accurate to 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let value = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Human-readable table of comparisons against the baseline `name`
+pub fn format_comparisons(name: &str, comparisons: &[Comparison], threshold: f64) -> String {
+    let mut output = format!("\nCompared with baseline '{}':\n", name);
+    if comparisons.is_empty() {
+        output.push_str("  No matching results in the baseline.\n");
+        return output;
+    }
+
+    for c in comparisons {
+        let verdict = if c.is_regression(threshold) {
+            "REGRESSION"
+        } else if !c.is_significant() {
+            "no significant change"
+        } else if c.change < 0.0 {
+            "faster"
+        } else {
+            "slower"
+        };
+        output.push_str(&format!(
+            "  {} \"{}\": {} -> {} ({:+.1}%, p = {:.3}) {}\n",
+            c.operation,
+            c.input,
+            super::BenchmarkStats::format_duration(c.baseline_mean),
+            super::BenchmarkStats::format_duration(c.current_mean),
+            c.change,
+            c.p_value,
+            verdict
+        ));
+    }
+
+    let regressions = comparisons
+        .iter()
+        .filter(|c| c.is_regression(threshold))
+        .count();
+    if regressions > 0 {
+        output.push_str(&format!(
+            "{} regression(s) beyond the {}% threshold\n",
+            regressions, threshold
+        ));
+    }
+    output
+}
+
+/// Sparklines of each result's mean across saved baselines, ending with this run
+///
+/// `history` is ordered oldest first, as returned by [`history`]. Results
+/// found in no baseline are left out, and only the latest points that fit
+/// in `width` are drawn.
+pub fn format_trends(
+    results: &[BenchmarkStats],
+    history: &[Vec<BaselineEntry>],
+    width: usize,
+) -> String {
+    let d = super::BenchmarkStats::format_duration;
+    let mut lines = Vec::new();
+
+    for stats in results {
+        let current = stats
+            .summary
+            .as_ref()
+            .map_or(stats.avg_duration, |s| s.mean);
+        let mut means: Vec<Duration> = history
+            .iter()
+            .filter_map(|entries| {
+                entries
+                    .iter()
+                    .find(|e| e.operation == stats.operation && e.input == stats.input)
+                    .map(|e| e.mean)
+            })
+            .collect();
+        if means.is_empty() {
+            continue;
+        }
+        means.push(current);
+
+        let label = format!("  {} \"{}\" ", stats.operation, stats.input);
+        let values = format!(" {} → {}", d(means[0]), d(current));
+        let room = width.saturating_sub(label.chars().count() + values.chars().count());
+        let shown = &means[means.len().saturating_sub(room.max(2))..];
+        let seconds: Vec<f64> = shown.iter().map(Duration::as_secs_f64).collect();
+        lines.push(format!(
+            "{}{} {} → {}",
+            label,
+            super::chart::sparkline(&seconds),
+            d(shown[0]),
+            d(current)
+        ));
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\nTrend across saved baselines, oldest to now:\n{}\n",
+        lines.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(input: &str, samples: &[u64]) -> BenchmarkStats {
+        let samples: Vec<Duration> = samples
+            .iter()
+            .map(|&us| Duration::from_micros(us))
+            .collect();
+        BenchmarkStats::new(
+            "Anagram Generation".to_string(),
+            input.to_string(),
+            samples.iter().sum(),
+            samples.len(),
+        )
+        .with_samples(samples)
+    }
+
+    fn environment() -> Environment {
+        Environment {
+            timestamp: "2024-03-01T12:00:00Z".to_string(),
+            git_revision: None,
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpus: 1,
+            cpu_model: None,
+        }
+    }
+
+    #[test]
+    fn test_erf_and_p_values() {
+        assert!(erf(0.0).abs() < 1e-7);
+        assert!((erf(1.0) - 0.842_700_8).abs() < 1e-6);
+        assert!((erf(-1.0) + 0.842_700_8).abs() < 1e-6);
+
+        // t = 1.96 gives the classic p = 0.05
+        let p = welch_p_value((1.96, 1.0, 1), (0.0, 0.0, 1));
+        assert!((p - 0.05).abs() < 1e-3, "{}", p);
+        assert_eq!(welch_p_value((5.0, 0.0, 10), (5.0, 0.0, 10)), 1.0);
+        assert_eq!(welch_p_value((5.0, 0.0, 10), (6.0, 0.0, 10)), 0.0);
+    }
+
+    #[test]
+    fn test_compare_detects_regressions() {
+        let before = [100, 101, 99, 100, 102, 98, 100, 101, 99, 100];
+        let slower = [120, 121, 119, 120, 122, 118, 120, 121, 119, 120];
+        let noisy = [90, 110, 95, 105, 100, 100, 92, 108, 97, 103];
+
+        let baseline = parse(&to_json(&[stats("cat", &before)], &environment())).unwrap();
+        assert_eq!(baseline[0].mean, Duration::from_micros(100));
+        assert_eq!(baseline[0].samples, 10);
+
+        let comparisons = compare(&[stats("cat", &slower), stats("dog", &slower)], &baseline);
+        assert_eq!(comparisons.len(), 1, "only matching inputs are compared");
+        let c = &comparisons[0];
+        assert!((c.change - 20.0).abs() < 1e-9);
+        assert!(c.is_significant());
+        assert!(c.is_regression(10.0));
+        assert!(!c.is_regression(25.0));
+
+        let c = &compare(&[stats("cat", &noisy)], &baseline)[0];
+        assert!(!c.is_significant());
+        assert!(!c.is_regression(0.0));
+
+        let report =
+            format_comparisons("main", &compare(&[stats("cat", &slower)], &baseline), 10.0);
+        assert!(report.contains("+20.0%"));
+        assert!(report.contains("REGRESSION"));
+        assert!(report.contains("1 regression(s) beyond the 10% threshold"));
+    }
+
+    #[test]
+    fn test_save_merges_and_loads() {
+        let dir = std::env::temp_dir().join(format!("augusto-baselines-{}", std::process::id()));
+        let both = [stats("cat", &[10, 12]), stats("dog", &[20])];
+
+        save(&dir, "main", &both, &environment()).unwrap();
+        let path = save(&dir, "main", &[stats("cat", &[30, 30])], &environment()).unwrap();
+        assert_eq!(path, dir.join("main.json"));
+
+        let entries = load(&dir, "main").unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].input, "dog");
+        assert_eq!(entries[1].input, "cat");
+        assert_eq!(entries[1].mean, Duration::from_micros(30));
+
+        assert!(load(&dir, "../etc/passwd").is_err());
+        assert!(load(&dir, "missing").is_err());
+        assert!(save(&dir, "", &[], &environment()).is_err());
+    }
+
+    #[test]
+    fn test_history_and_trends() {
+        let dir = std::env::temp_dir().join(format!("augusto-history-{}", std::process::id()));
+        let at = |timestamp: &str| Environment {
+            timestamp: timestamp.to_string(),
+            ..environment()
+        };
+
+        // Saved out of order, read back by timestamp
+        save(
+            &dir,
+            "v2",
+            &[stats("cat", &[20, 20])],
+            &at("2024-02-01T00:00:00Z"),
+        )
+        .unwrap();
+        save(
+            &dir,
+            "v1",
+            &[stats("cat", &[10, 10])],
+            &at("2024-01-01T00:00:00Z"),
+        )
+        .unwrap();
+        save(
+            &dir,
+            "other",
+            &[stats("dog", &[5])],
+            &at("2024-03-01T00:00:00Z"),
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.json"), "not json").unwrap();
+        let history = history(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0][0].mean, Duration::from_micros(10));
+
+        let trends = format_trends(&[stats("cat", &[40, 40]), stats("new", &[1])], &history, 80);
+        assert!(trends.contains("\"cat\" ▁▃█ 10μs → 40μs"), "{}", trends);
+        assert!(!trends.contains("new"));
+        assert_eq!(format_trends(&[stats("new", &[1])], &history, 80), "");
+        assert!(super::history(&dir).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+//! Terminal charts of benchmark results
+//!
+//! Bar charts compare the entries of a suite, a histogram shows how the
+//! samples of one benchmark are spread, and sparklines show the trend of a
+//! result across saved baselines. Charts fill the given width, normally the
+//! width of the terminal from [`terminal_width`].
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use augusto::benchmark::chart::{bar_chart, sparkline};
+//!
+//! let entries = [("cat".to_string(), Duration::from_micros(2)),
+//!                ("test".to_string(), Duration::from_micros(8))];
+//! println!("{}", bar_chart(&entries, 60));
+//! assert_eq!(sparkline(&[1.0, 2.0, 3.0]), "▁▅█");
+//! ```
+
+use std::time::Duration;
+
+use super::BenchmarkStats;
+
+/// Width used when stdout is not a terminal
+const DEFAULT_WIDTH: usize = 80;
+
+/// Narrowest bar area drawn, however small the terminal
+const MIN_BAR_WIDTH: usize = 10;
+
+/// Most rows of a histogram
+const MAX_BINS: usize = 12;
+
+/// Partial blocks, in eighths of a character
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Levels of a sparkline, lowest first
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Columns of the terminal stdout is attached to, or 80
+pub fn terminal_width() -> usize {
+    termion::terminal_size()
+        .map(|(columns, _)| columns as usize)
+        .ok()
+        .filter(|&columns| columns > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// A bar `fraction` (0 to 1) of `width` characters long, with eighth-block precision
+fn bar(fraction: f64, width: usize) -> String {
+    let mut eighths = (fraction.clamp(0.0, 1.0) * width as f64 * 8.0).round() as usize;
+    // Keep non-zero values visible next to much larger ones
+    if fraction > 0.0 {
+        eighths = eighths.max(1);
+    }
+    let mut bar = "█".repeat(eighths / 8);
+    match eighths % 8 {
+        0 => {}
+        partial => bar.push(EIGHTHS[partial]),
+    }
+    bar
+}
+
+/// At most `width` characters of `text`, ending in `…` if it was cut
+pub(super) fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+/// Labelled rows of bars proportional to their values, each followed by its text
+fn bar_rows(rows: &[(String, f64, String)], width: usize) -> String {
+    let label_width = rows
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(width / 3);
+    let value_width = rows
+        .iter()
+        .map(|(_, _, value)| value.chars().count())
+        .max()
+        .unwrap_or(0);
+    let bar_width = width
+        .saturating_sub(label_width + value_width + 3)
+        .max(MIN_BAR_WIDTH);
+    let largest = rows.iter().map(|(_, v, _)| *v).fold(0.0, f64::max);
+
+    let mut output = String::new();
+    for (label, value, text) in rows {
+        let fraction = if largest > 0.0 { value / largest } else { 0.0 };
+        output.push_str(&format!(
+            "{:<label_width$} │{:<bar_width$} {}\n",
+            truncate(label, label_width),
+            bar(fraction, bar_width),
+            text,
+        ));
+    }
+    output
+}
+
+/// Horizontal bars comparing durations, scaled so the longest fills the width
+pub fn bar_chart(entries: &[(String, Duration)], width: usize) -> String {
+    let rows: Vec<(String, f64, String)> = entries
+        .iter()
+        .map(|(label, duration)| {
+            (
+                label.clone(),
+                duration.as_secs_f64(),
+                BenchmarkStats::format_duration(*duration),
+            )
+        })
+        .collect();
+    bar_rows(&rows, width)
+}
+
+/// How many samples fall in each range of times
+///
+/// The ranges split the samples up to the 99th percentile evenly; slower
+/// samples are counted in a last row so they do not squash the others.
+pub fn histogram(samples: &[Duration], width: usize) -> String {
+    if samples.is_empty() {
+        return String::new();
+    }
+
+    let mut sorted: Vec<Duration> = samples.to_vec();
+    sorted.sort();
+    let low = sorted[0];
+    let high = sorted[(sorted.len() - 1) * 99 / 100];
+    let bins = MAX_BINS.min(sorted.len());
+    let step = (high - low) / bins as u32;
+
+    let mut counts = vec![0usize; bins];
+    let mut slower = 0;
+    for &sample in &sorted {
+        if sample > high {
+            slower += 1;
+        } else if step.is_zero() {
+            counts[0] += 1;
+        } else {
+            let bin = ((sample - low).as_nanos() / step.as_nanos()) as usize;
+            counts[bin.min(bins - 1)] += 1;
+        }
+    }
+    if step.is_zero() {
+        counts.truncate(1);
+    }
+
+    // Bins narrower than a microsecond need more precision than the report uses
+    let d = |time: Duration| {
+        if step < Duration::from_micros(1)
+            && time >= Duration::from_micros(1)
+            && time < Duration::from_millis(1)
+        {
+            format!("{:.2}μs", time.as_nanos() as f64 / 1000.0)
+        } else {
+            BenchmarkStats::format_duration(time)
+        }
+    };
+    let mut rows: Vec<(String, f64, String)> = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let from = low + step * i as u32;
+            let label = if step.is_zero() {
+                d(from)
+            } else {
+                // The last bin also holds the rounding left over by `step`
+                let to = if i + 1 == bins { high } else { from + step };
+                format!("{} – {}", d(from), d(to))
+            };
+            (label, count as f64, count.to_string())
+        })
+        .collect();
+    if slower > 0 {
+        rows.push((format!("> {}", d(high)), slower as f64, slower.to_string()));
+    }
+    bar_rows(&rows, width)
+}
+
+/// One character per value, from `▁` for the smallest to `█` for the largest
+pub fn sparkline(values: &[f64]) -> String {
+    let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&value| {
+            if high > low {
+                let level = ((value - low) / (high - low) * (LEVELS.len() - 1) as f64).round();
+                LEVELS[level as usize]
+            } else {
+                LEVELS[0]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_chart_scales_to_width() {
+        let entries = [
+            ("cat".to_string(), Duration::from_micros(2)),
+            ("program".to_string(), Duration::from_micros(8)),
+        ];
+        let chart = bar_chart(&entries, 40);
+        let lines: Vec<&str> = chart.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("cat     │"));
+        assert!(lines[1].ends_with(" 8μs"));
+        // The bar area is 40 - 7 (label) - 3 (value) - 3 characters wide
+        assert_eq!(lines[1].matches('█').count(), 27);
+        assert_eq!(lines[0].matches('█').count(), 6);
+        assert!(lines.iter().all(|line| line.chars().count() == 40));
+    }
+
+    #[test]
+    fn test_histogram_counts_samples() {
+        let mut samples: Vec<Duration> = (0..100)
+            .map(|i| Duration::from_micros(10 + i % 4))
+            .collect();
+        samples.push(Duration::from_millis(5));
+
+        let chart = histogram(&samples, 60);
+        let total: usize = chart
+            .lines()
+            .map(|line| line.rsplit(' ').next().unwrap().parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total, samples.len());
+        assert!(chart.lines().last().unwrap().starts_with("> 13.00μs"));
+        assert!(chart
+            .lines()
+            .next()
+            .unwrap()
+            .starts_with("10.00μs – 10.25μs"));
+
+        let same = histogram(&[Duration::from_micros(3); 5], 40);
+        assert_eq!(same.lines().count(), 1);
+        assert!(same.ends_with(" 5\n"));
+        assert_eq!(histogram(&[], 40), "");
+    }
+
+    #[test]
+    fn test_sparkline_and_helpers() {
+        assert_eq!(sparkline(&[1.0, 2.0, 3.0]), "▁▅█");
+        assert_eq!(sparkline(&[4.0, 4.0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(bar(0.5, 3), "█▌");
+        assert_eq!(bar(0.001, 3), "▏");
+        assert_eq!(bar(0.0, 3), "");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+    }
+}
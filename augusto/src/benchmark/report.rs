@@ -0,0 +1,429 @@
+//! Machine-readable benchmark reports
+//!
+//! Results can be written as JSON or CSV for CI and other tools. Both
+//! formats carry every statistic of each result along with the
+//! [`Environment`] the benchmark ran in: a UTC timestamp, the git revision
+//! of the working directory (when there is one) and basic machine details.
+//! Durations are whole nanoseconds.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::benchmark::{benchmark_operation, BenchmarkConfig};
+//! use augusto::benchmark::report::{to_json, Environment};
+//!
+//! let stats = benchmark_operation("len", "abc", &BenchmarkConfig::default(), || "abc".len());
+//! println!("{}", to_json(&[stats], &Environment::capture()));
+//! ```
+
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::BenchmarkStats;
+
+/// How benchmark results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The human-readable report
+    Text,
+    /// One JSON document with the environment and all results
+    Json,
+    /// A header line and one row per result
+    Csv,
+}
+
+impl OutputFormat {
+    /// Parse an output format from its command-line name
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "Unknown output format '{}'. Expected text, json or csv.",
+                name
+            )),
+        }
+    }
+}
+
+/// Where and when a benchmark ran
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Environment {
+    /// UTC time the report was made, as RFC 3339
+    pub timestamp: String,
+    /// Commit checked out in the working directory, if it is a git repository
+    pub git_revision: Option<String>,
+    /// Operating system, e.g. `linux`
+    pub os: String,
+    /// CPU architecture, e.g. `x86_64`
+    pub arch: String,
+    /// Number of CPUs available to the process
+    pub cpus: usize,
+    /// CPU model name, where the platform reports one
+    pub cpu_model: Option<String>,
+}
+
+impl Environment {
+    /// Describe the current machine and working directory
+    pub fn capture() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Self {
+            timestamp: format_timestamp(now.as_secs()),
+            git_revision: git_revision(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            cpu_model: cpu_model(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "timestamp": self.timestamp,
+            "git_revision": self.git_revision,
+            "os": self.os,
+            "arch": self.arch,
+            "cpus": self.cpus,
+            "cpu_model": self.cpu_model,
+        })
+    }
+}
+
+/// Output of `git rev-parse HEAD`, with `-dirty` for uncommitted changes
+fn git_revision() -> Option<String> {
+    let run = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let revision = run(&["rev-parse", "HEAD"]).filter(|r| !r.is_empty())?;
+    let dirty = run(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.is_empty());
+    Some(if dirty {
+        format!("{}-dirty", revision)
+    } else {
+        revision
+    })
+}
+
+/// CPU model from `/proc/cpuinfo`, on systems that have it
+fn cpu_model() -> Option<String> {
+    let info = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    info.lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+/// RFC 3339 UTC time for seconds since the Unix epoch
+fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Whole nanoseconds of a duration
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().min(u64::MAX as u128) as u64
+}
+
+/// Runs per second implied by the total time, if any time was measured
+fn throughput(stats: &BenchmarkStats) -> Option<f64> {
+    (stats.duration > Duration::ZERO)
+        .then(|| stats.iterations as f64 / stats.duration.as_secs_f64())
+}
+
+/// One result as a JSON object
+fn stats_to_json(stats: &BenchmarkStats) -> Value {
+    let summary = stats.summary.as_ref().map(|s| {
+        json!({
+            "min_ns": nanos(s.min),
+            "max_ns": nanos(s.max),
+            "mean_ns": nanos(s.mean),
+            "median_ns": nanos(s.median),
+            "std_dev_ns": nanos(s.std_dev),
+            "p95_ns": nanos(s.p95),
+            "p99_ns": nanos(s.p99),
+            "ci95_low_ns": nanos(s.ci_low),
+            "ci95_high_ns": nanos(s.ci_high),
+            "outliers": {
+                "low_severe": s.outliers.low_severe,
+                "low_mild": s.outliers.low_mild,
+                "high_mild": s.outliers.high_mild,
+                "high_severe": s.outliers.high_severe,
+            },
+        })
+    });
+
+    json!({
+        "operation": stats.operation,
+        "input": stats.input,
+        "input_length": stats.input.chars().count(),
+        "output_size": stats.output_size,
+        "iterations": stats.iterations,
+        "samples": stats.samples.len(),
+        "total_ns": nanos(stats.duration),
+        "avg_ns": nanos(stats.avg_duration),
+        "throughput_per_sec": throughput(stats),
+        "statistics": summary,
+        "memory": stats.memory.map(|m| json!({
+            "bytes_per_run": m.bytes,
+            "allocations_per_run": m.allocations,
+            "peak_bytes": m.peak,
+        })),
+    })
+}
+
+/// Results and their environment as a pretty-printed JSON document
+pub fn to_json(results: &[BenchmarkStats], environment: &Environment) -> String {
+    let document = json!({
+        "environment": environment.to_json(),
+        "results": results.iter().map(stats_to_json).collect::<Vec<_>>(),
+    });
+    // Serialising a `Value` cannot fail
+    serde_json::to_string_pretty(&document).unwrap_or_default() + "\n"
+}
+
+/// Column names of the CSV output
+const CSV_COLUMNS: [&str; 32] = [
+    "timestamp",
+    "git_revision",
+    "os",
+    "arch",
+    "cpus",
+    "cpu_model",
+    "operation",
+    "input",
+    "input_length",
+    "output_size",
+    "iterations",
+    "samples",
+    "total_ns",
+    "avg_ns",
+    "throughput_per_sec",
+    "min_ns",
+    "max_ns",
+    "mean_ns",
+    "median_ns",
+    "std_dev_ns",
+    "p95_ns",
+    "p99_ns",
+    "ci95_low_ns",
+    "ci95_high_ns",
+    "outliers",
+    "low_severe",
+    "low_mild",
+    "high_mild",
+    "high_severe",
+    "bytes_per_run",
+    "allocations_per_run",
+    "peak_bytes",
+];
+
+/// Number of CSV columns filled from a result's [`Summary`](super::stats::Summary)
+const SUMMARY_COLUMNS: usize = 14;
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Results as CSV, one row per result with the environment repeated
+///
+/// Missing values, such as the statistics of a result without samples, are
+/// empty fields.
+pub fn to_csv(results: &[BenchmarkStats], environment: &Environment) -> String {
+    let mut output = CSV_COLUMNS.join(",");
+    output.push('\n');
+
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    for stats in results {
+        let mut fields = vec![
+            environment.timestamp.clone(),
+            optional(environment.git_revision.clone()),
+            environment.os.clone(),
+            environment.arch.clone(),
+            environment.cpus.to_string(),
+            optional(environment.cpu_model.clone()),
+            stats.operation.clone(),
+            stats.input.clone(),
+            stats.input.chars().count().to_string(),
+            optional(stats.output_size.map(|n| n.to_string())),
+            stats.iterations.to_string(),
+            stats.samples.len().to_string(),
+            nanos(stats.duration).to_string(),
+            nanos(stats.avg_duration).to_string(),
+            optional(throughput(stats).map(|t| format!("{:.2}", t))),
+        ];
+
+        match &stats.summary {
+            Some(s) => {
+                fields.extend(
+                    [
+                        s.min, s.max, s.mean, s.median, s.std_dev, s.p95, s.p99, s.ci_low,
+                        s.ci_high,
+                    ]
+                    .iter()
+                    .map(|&d| nanos(d).to_string()),
+                );
+                fields.extend(
+                    [
+                        s.outliers.total(),
+                        s.outliers.low_severe,
+                        s.outliers.low_mild,
+                        s.outliers.high_mild,
+                        s.outliers.high_severe,
+                    ]
+                    .iter()
+                    .map(usize::to_string),
+                );
+            }
+            None => fields.extend(std::iter::repeat_n(String::new(), SUMMARY_COLUMNS)),
+        }
+
+        match stats.memory {
+            Some(m) => fields.extend([m.bytes, m.allocations, m.peak].map(|n| n.to_string())),
+            None => fields.extend(std::iter::repeat_n(String::new(), 3)),
+        }
+
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::alloc::AllocStats;
+    use super::*;
+
+    fn environment() -> Environment {
+        Environment {
+            timestamp: "2024-03-01T12:00:00Z".to_string(),
+            git_revision: Some("abc123".to_string()),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            cpus: 8,
+            cpu_model: Some("Test CPU, 3 GHz".to_string()),
+        }
+    }
+
+    fn results() -> Vec<BenchmarkStats> {
+        let samples = [10, 12, 11, 13].map(Duration::from_micros).to_vec();
+        vec![
+            BenchmarkStats::new(
+                "Anagram Generation".to_string(),
+                "cat".to_string(),
+                Duration::from_micros(46),
+                4,
+            )
+            .with_samples(samples)
+            .with_output_size(6)
+            .with_memory(AllocStats {
+                bytes: 2048,
+                allocations: 7,
+                peak: 1024,
+            }),
+            BenchmarkStats::new(
+                "op".to_string(),
+                "say \"hi\"".to_string(),
+                Duration::ZERO,
+                0,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!(OutputFormat::parse("JSON"), Ok(OutputFormat::Json));
+        assert_eq!(OutputFormat::parse("csv"), Ok(OutputFormat::Csv));
+        assert_eq!(OutputFormat::parse("text"), Ok(OutputFormat::Text));
+        assert!(OutputFormat::parse("xml").is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_294_400), "2024-03-01T12:00:00Z");
+    }
+
+    #[test]
+    fn test_json_report() {
+        let document: Value = serde_json::from_str(&to_json(&results(), &environment())).unwrap();
+
+        assert_eq!(document["environment"]["git_revision"], "abc123");
+        assert_eq!(document["environment"]["cpus"], 8);
+
+        let first = &document["results"][0];
+        assert_eq!(first["operation"], "Anagram Generation");
+        assert_eq!(first["input_length"], 3);
+        assert_eq!(first["output_size"], 6);
+        assert_eq!(first["total_ns"], 46_000);
+        assert_eq!(first["statistics"]["median_ns"], 11_500);
+        assert_eq!(first["statistics"]["outliers"]["high_severe"], 0);
+        assert_eq!(first["memory"]["allocations_per_run"], 7);
+
+        let second = &document["results"][1];
+        assert_eq!(second["statistics"], Value::Null);
+        assert_eq!(second["throughput_per_sec"], Value::Null);
+        assert_eq!(second["memory"], Value::Null);
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let csv = to_csv(&results(), &environment());
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].split(',').count(), CSV_COLUMNS.len());
+        assert!(
+            lines[1].starts_with("2024-03-01T12:00:00Z,abc123,linux,x86_64,8,\"Test CPU, 3 GHz\",")
+        );
+        assert!(lines[1].contains(",Anagram Generation,cat,3,6,4,4,46000,11500,"));
+        assert!(lines[2].contains(",op,\"say \"\"hi\"\"\",8,,0,0,0,0,,"));
+        assert!(lines[1].ends_with(",2048,7,1024"));
+        assert_eq!(
+            lines[2].split(',').count(),
+            CSV_COLUMNS.len() + 1,
+            "one quoted comma"
+        );
+        assert!(lines[2].ends_with(&",".repeat(SUMMARY_COLUMNS + 3)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Empirical complexity of an operation across input sizes
+//!
+//! The mean time per run measured at several input sizes is fitted to each
+//! [`Complexity`] class as `time ≈ c · cost(n)`. The fit minimises the
+//! relative error at every size, so the fast small inputs weigh as much as
+//! the slow large ones; the class with the smallest error is the best fit.
+//!
+//! # Examples
+//!
+//! ```
+//! use augusto::benchmark::scaling::{fit, Complexity};
+//!
+//! let points: Vec<(usize, f64)> = (1..=6).map(|n| (n, 3.0 * (n * n) as f64)).collect();
+//! assert_eq!(fit(&points)[0].complexity, Complexity::Quadratic);
+//! ```
+
+use std::time::Duration;
+
+use super::chart::truncate;
+use super::BenchmarkStats;
+
+/// Characters of a chart row besides the bar: the length and the time
+const CHART_MARGIN: usize = 16;
+
+/// Narrowest chart bar, however small the terminal
+const MIN_CHART_WIDTH: usize = 20;
+
+/// A candidate growth rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Complexity {
+    /// O(n)
+    Linear,
+    /// O(n log n)
+    Linearithmic,
+    /// O(n²)
+    Quadratic,
+    /// O(n!·n), e.g. listing every permutation
+    Factorial,
+}
+
+impl Complexity {
+    /// Every candidate, from slowest to fastest growing
+    pub const ALL: [Complexity; 4] = [
+        Complexity::Linear,
+        Complexity::Linearithmic,
+        Complexity::Quadratic,
+        Complexity::Factorial,
+    ];
+
+    /// Big-O notation of the class
+    pub fn name(self) -> &'static str {
+        match self {
+            Complexity::Linear => "O(n)",
+            Complexity::Linearithmic => "O(n log n)",
+            Complexity::Quadratic => "O(n²)",
+            Complexity::Factorial => "O(n!·n)",
+        }
+    }
+
+    /// Relative cost of an input of size `n`
+    pub fn cost(self, n: usize) -> f64 {
+        let n = n as f64;
+        match self {
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * n.log2(),
+            Complexity::Quadratic => n * n,
+            Complexity::Factorial => (1..=n as u64).map(|k| k as f64).product::<f64>() * n,
+        }
+    }
+}
+
+/// How well a complexity class explains the measurements
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fit {
+    /// The fitted class
+    pub complexity: Complexity,
+    /// Seconds per unit of [`Complexity::cost`]
+    pub coefficient: f64,
+    /// Root mean square of the relative error at each size
+    pub error: f64,
+}
+
+impl Fit {
+    /// Time the fitted curve predicts for an input of size `n`
+    pub fn predict(&self, n: usize) -> Duration {
+        Duration::from_secs_f64((self.coefficient * self.complexity.cost(n)).max(0.0))
+    }
+}
+
+/// Fit every complexity class to `(size, seconds)` points, best fit first
+pub fn fit(points: &[(usize, f64)]) -> Vec<Fit> {
+    let mut fits: Vec<Fit> = Complexity::ALL
+        .iter()
+        .map(|&complexity| fit_one(complexity, points))
+        .collect();
+    fits.sort_by(|a, b| a.error.total_cmp(&b.error));
+    fits
+}
+
+/// Weighted least squares of `t ≈ c · cost(n)` with weights `1 / t²`
+fn fit_one(complexity: Complexity, points: &[(usize, f64)]) -> Fit {
+    let points: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|&&(_, t)| t > 0.0)
+        .map(|&(n, t)| (complexity.cost(n), t))
+        .collect();
+
+    let numerator: f64 = points.iter().map(|(g, t)| g / t).sum();
+    let denominator: f64 = points.iter().map(|(g, t)| (g / t).powi(2)).sum();
+    let coefficient = if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    };
+
+    let squared: f64 = points
+        .iter()
+        .map(|(g, t)| ((coefficient * g - t) / t).powi(2))
+        .sum();
+    let error = if points.is_empty() {
+        f64::INFINITY
+    } else {
+        (squared / points.len() as f64).sqrt()
+    };
+
+    Fit {
+        complexity,
+        coefficient,
+        error,
+    }
+}
+
+/// Input of `n` characters made of distinct letters where possible
+///
+/// Distinct letters are the worst case of anagram generation, since no
+/// permutations repeat.
+pub fn input_of_length(n: usize, uppercase: bool) -> String {
+    let first = if uppercase { b'A' } else { b'a' };
+    (0..n).map(|i| (first + (i % 26) as u8) as char).collect()
+}
+
+/// Report the timings at each size, the fits and a log-scale chart
+///
+/// `results` holds one benchmark per size, in the same order as `sizes`.
+/// The chart fills `width` columns.
+pub fn format_scaling(sizes: &[usize], results: &[BenchmarkStats], width: usize) -> String {
+    let d = BenchmarkStats::format_duration;
+    let points: Vec<(usize, f64)> = sizes
+        .iter()
+        .zip(results)
+        .map(|(&n, stats)| (n, stats.avg_duration.as_secs_f64()))
+        .collect();
+    let fits = fit(&points);
+    let operation = results.first().map_or("", |stats| stats.operation.as_str());
+
+    let mut output = String::new();
+    output.push_str("\n╔════════════════════════════════════════════════════════════╗\n");
+    output.push_str("║              COMPLEXITY SCALING                            ║\n");
+    output.push_str("╚════════════════════════════════════════════════════════════╝\n\n");
+    output.push_str(&format!("Operation:        {}\n\n", operation));
+
+    output.push_str("   n   Input                  Mean per run   Output\n");
+    for (&n, stats) in sizes.iter().zip(results) {
+        let output_size = stats
+            .output_size
+            .map_or_else(String::new, |size| size.to_string());
+        let line = format!(
+            "{:>4}   {:<20}   {:>12}   {}",
+            n,
+            truncate(&stats.input, 20),
+            d(stats.avg_duration),
+            output_size
+        );
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+
+    output.push_str("\nFit                Error\n");
+    for fit in &fits {
+        output.push_str(&format!(
+            "{:<14}   {:>7.1}%\n",
+            fit.complexity.name(),
+            fit.error * 100.0
+        ));
+    }
+
+    if let Some(best) = fits.first() {
+        output.push_str(&format!(
+            "\nBest fit:         {}\n\n",
+            best.complexity.name()
+        ));
+        output.push_str(&format_chart(&points, best, width));
+        output.push_str(&format!(
+            "\n     █ measured   ◆ {} fit (log scale)\n",
+            best.complexity.name()
+        ));
+    }
+
+    output.push_str("\n╚════════════════════════════════════════════════════════════╝\n");
+    output
+}
+
+/// One bar per size on a log time scale, with the fitted time marked
+fn format_chart(points: &[(usize, f64)], best: &Fit, width: usize) -> String {
+    let bar_width = width.saturating_sub(CHART_MARGIN).max(MIN_CHART_WIDTH);
+    let predicted: Vec<f64> = points
+        .iter()
+        .map(|&(n, _)| best.predict(n).as_secs_f64())
+        .collect();
+    let logs: Vec<f64> = points
+        .iter()
+        .map(|&(_, t)| t)
+        .chain(predicted.iter().copied())
+        .filter(|&t| t > 0.0)
+        .map(f64::log10)
+        .collect();
+    let low = logs.iter().copied().fold(f64::INFINITY, f64::min);
+    let high = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    // Columns 1..=bar_width, so that every positive time gets a visible bar
+    let column = |t: f64| -> usize {
+        if t <= 0.0 || !low.is_finite() {
+            return 0;
+        }
+        let span = (high - low).max(f64::EPSILON);
+        1 + ((t.log10() - low) / span * (bar_width - 1) as f64).round() as usize
+    };
+
+    let mut output = String::new();
+    for (&(n, t), &p) in points.iter().zip(&predicted) {
+        let mut bar: Vec<char> = vec![' '; bar_width];
+        bar.iter_mut().take(column(t)).for_each(|c| *c = '█');
+        let marker = column(p);
+        if marker > 0 {
+            bar[marker - 1] = '◆';
+        }
+        output.push_str(&format!(
+            "{:>4} │{} {}\n",
+            n,
+            bar.into_iter().collect::<String>(),
+            BenchmarkStats::format_duration(Duration::from_secs_f64(t))
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(complexity: Complexity, sizes: std::ops::RangeInclusive<usize>) -> Vec<(usize, f64)> {
+        sizes.map(|n| (n, 2e-7 * complexity.cost(n))).collect()
+    }
+
+    #[test]
+    fn test_fit_finds_each_complexity() {
+        for complexity in Complexity::ALL {
+            let fits = fit(&points(complexity, 3..=10));
+            assert_eq!(fits[0].complexity, complexity);
+            assert!(fits[0].error < 1e-9, "{:?}", fits[0]);
+            assert!((fits[0].coefficient - 2e-7).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_fit_tolerates_noise() {
+        // Factorial growth with ±10% noise is still far from polynomial
+        let noisy: Vec<(usize, f64)> = points(Complexity::Factorial, 3..=9)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (n, t))| (n, t * if i % 2 == 0 { 1.1 } else { 0.9 }))
+            .collect();
+
+        let fits = fit(&noisy);
+        assert_eq!(fits[0].complexity, Complexity::Factorial);
+        assert!(fits[0].error < 0.15);
+        assert!(fits[1].error > 0.5);
+    }
+
+    #[test]
+    fn test_costs_and_inputs() {
+        assert_eq!(Complexity::Factorial.cost(4), 96.0);
+        assert_eq!(Complexity::Linearithmic.cost(8), 24.0);
+        assert_eq!(input_of_length(4, false), "abcd");
+        assert_eq!(input_of_length(3, true), "ABC");
+        assert_eq!(input_of_length(28, false).len(), 28);
+    }
+
+    #[test]
+    fn test_format_scaling_report() {
+        let sizes = [3, 4, 5];
+        let results: Vec<BenchmarkStats> = sizes
+            .iter()
+            .map(|&n| {
+                let per_run = Duration::from_nanos(100 * (n * n) as u64);
+                BenchmarkStats::new(
+                    "Anagram Generation".to_string(),
+                    input_of_length(n, false),
+                    per_run * 10,
+                    10,
+                )
+            })
+            .collect();
+
+        let report = format_scaling(&sizes, &results, 56);
+        assert!(report.contains("Best fit:         O(n²)"));
+        assert!(report.contains("abcde"));
+        // The largest size gets the full bar, with the fit marker at its end
+        let last_bar = report
+            .lines()
+            .find(|line| line.starts_with("   5 │"))
+            .unwrap();
+        assert_eq!(last_bar.matches('█').count(), 56 - CHART_MARGIN - 1);
+        assert!(last_bar.contains('◆'));
+    }
+}
@@ -0,0 +1,186 @@
+//! Summary statistics over benchmark samples
+//!
+//! A [`Summary`] describes the distribution of per-iteration timings: its
+//! range, centre and spread, the tail percentiles, a 95% confidence interval
+//! for the mean, and the samples that fall outside Tukey's fences.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//! use augusto::benchmark::stats::Summary;
+//!
+//! let samples: Vec<Duration> = (1..=10).map(Duration::from_micros).collect();
+//! let summary = Summary::from_samples(&samples).unwrap();
+//! assert_eq!(summary.median, Duration::from_nanos(5500));
+//! ```
+
+use std::time::Duration;
+
+/// z-score of a two-sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// Samples outside Tukey's fences
+///
+/// Mild outliers lie more than 1.5 interquartile ranges outside the first or
+/// third quartile, severe ones more than 3.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outliers {
+    /// More than 3 IQR below the first quartile
+    pub low_severe: usize,
+    /// Between 1.5 and 3 IQR below the first quartile
+    pub low_mild: usize,
+    /// Between 1.5 and 3 IQR above the third quartile
+    pub high_mild: usize,
+    /// More than 3 IQR above the third quartile
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    /// Number of outliers of any kind
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+/// Distribution of a set of timing samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Fastest sample
+    pub min: Duration,
+    /// Slowest sample
+    pub max: Duration,
+    /// Arithmetic mean
+    pub mean: Duration,
+    /// Middle sample (mean of the two middle ones for an even count)
+    pub median: Duration,
+    /// Sample standard deviation
+    pub std_dev: Duration,
+    /// 95th percentile
+    pub p95: Duration,
+    /// 99th percentile
+    pub p99: Duration,
+    /// Lower end of the 95% confidence interval for the mean
+    pub ci_low: Duration,
+    /// Upper end of the 95% confidence interval for the mean
+    pub ci_high: Duration,
+    /// Samples outside Tukey's fences
+    pub outliers: Outliers,
+}
+
+impl Summary {
+    /// Summarise samples, or `None` if there are none
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len() as f64;
+
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let std_dev = variance.sqrt();
+        let margin = Z_95 * std_dev / n.sqrt();
+
+        let q1 = percentile(&sorted, 25.0);
+        let q3 = percentile(&sorted, 75.0);
+        let iqr = q3 - q1;
+        let mut outliers = Outliers::default();
+        for &x in &sorted {
+            if x < q1 - 3.0 * iqr {
+                outliers.low_severe += 1;
+            } else if x < q1 - 1.5 * iqr {
+                outliers.low_mild += 1;
+            } else if x > q3 + 3.0 * iqr {
+                outliers.high_severe += 1;
+            } else if x > q3 + 1.5 * iqr {
+                outliers.high_mild += 1;
+            }
+        }
+
+        Some(Self {
+            min: nanos(sorted[0]),
+            max: nanos(sorted[sorted.len() - 1]),
+            mean: nanos(mean),
+            median: nanos(percentile(&sorted, 50.0)),
+            std_dev: nanos(std_dev),
+            p95: nanos(percentile(&sorted, 95.0)),
+            p99: nanos(percentile(&sorted, 99.0)),
+            ci_low: nanos(mean - margin),
+            ci_high: nanos(mean + margin),
+            outliers,
+        })
+    }
+}
+
+/// Percentile `p` (0 to 100) of sorted values, interpolating between ranks
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// A duration from a (possibly negative) number of nanoseconds
+fn nanos(value: f64) -> Duration {
+    Duration::from_nanos(value.max(0.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_micros(v)).collect()
+    }
+
+    #[test]
+    fn test_summary_of_samples() {
+        let summary = Summary::from_samples(&micros(&[5, 1, 4, 2, 3])).unwrap();
+
+        assert_eq!(summary.min, Duration::from_micros(1));
+        assert_eq!(summary.max, Duration::from_micros(5));
+        assert_eq!(summary.mean, Duration::from_micros(3));
+        assert_eq!(summary.median, Duration::from_micros(3));
+        // Sample standard deviation of 1..=5 is sqrt(2.5)
+        assert_eq!(summary.std_dev, Duration::from_nanos(1581));
+        assert_eq!(summary.p95, Duration::from_nanos(4800));
+        assert_eq!(summary.p99, Duration::from_nanos(4960));
+        assert!(summary.ci_low < summary.mean && summary.mean < summary.ci_high);
+        assert_eq!(summary.outliers.total(), 0);
+    }
+
+    #[test]
+    fn test_single_and_no_samples() {
+        assert_eq!(Summary::from_samples(&[]), None);
+
+        let summary = Summary::from_samples(&micros(&[7])).unwrap();
+        assert_eq!(summary.std_dev, Duration::ZERO);
+        assert_eq!(summary.ci_low, summary.ci_high);
+        assert_eq!(summary.p99, Duration::from_micros(7));
+    }
+
+    #[test]
+    fn test_tukey_outliers() {
+        // Quartiles of 10..=29 plus the two slow samples are 15.25 and 25.75,
+        // so the fences are at 41.5 (mild) and 57.25 (severe)
+        let mut values: Vec<u64> = (10..30).collect();
+        values.extend([50, 90]);
+
+        let summary = Summary::from_samples(&micros(&values)).unwrap();
+        assert_eq!(
+            summary.outliers,
+            Outliers {
+                high_mild: 1,
+                high_severe: 1,
+                ..Outliers::default()
+            }
+        );
+        assert_eq!(summary.outliers.total(), 2);
+    }
+}
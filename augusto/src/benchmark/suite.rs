@@ -0,0 +1,380 @@
+//! Benchmark suites declared in a TOML file
+//!
+//! A suite file lists named scenarios. Each scenario names an operation,
+//! the inputs to run it on and options added after every input, and may set
+//! its own iteration policy. `[defaults]` sets the policy of every
+//! scenario, and `[report]` asks for machine-readable copies of the results:
+//!
+//! ```toml
+//! [defaults]
+//! time = "1s"
+//! warm-up = "200ms"
+//!
+//! [report]
+//! json = "bench.json"
+//! csv = "bench.csv"
+//!
+//! [[scenario]]
+//! name = "anagrams"
+//! operation = "anagram"
+//! inputs = ["cat", "test", "program"]
+//!
+//! [[scenario]]
+//! name = "png art"
+//! operation = "art"
+//! inputs = [["RUST", "code", "3"], ["LUXO", "LIXO"]]
+//! options = ["--format", "png"]
+//! min-samples = 50
+//! ```
+//!
+//! An input is a single argument or a list of arguments. The policy keys are
+//! `time`, `warm-up`, `min-samples` and `memory`; a scenario's own keys win
+//! over `[defaults]`, and options given on the command line win over both.
+//! Report paths are relative to the suite file.
+
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use super::{parse_duration, BenchmarkConfig};
+
+/// Keys of the iteration policy, accepted in `[defaults]` and in scenarios
+const POLICY_KEYS: [&str; 4] = ["time", "warm-up", "min-samples", "memory"];
+
+/// Keys of a scenario besides the policy
+const SCENARIO_KEYS: [&str; 4] = ["name", "operation", "inputs", "options"];
+
+/// One named scenario of a suite file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// Name shown while the suite runs
+    pub name: String,
+    /// Command to benchmark, as on the command line
+    pub operation: String,
+    /// Arguments of each input
+    pub inputs: Vec<Vec<String>>,
+    /// Arguments added after every input
+    pub options: Vec<String>,
+    /// Iteration policy after applying the defaults
+    pub config: BenchmarkConfig,
+}
+
+impl Scenario {
+    /// Arguments of each run: an input followed by the scenario's options
+    pub fn runs(&self) -> Vec<Vec<String>> {
+        self.inputs
+            .iter()
+            .map(|input| input.iter().chain(&self.options).cloned().collect())
+            .collect()
+    }
+}
+
+/// The contents of a suite file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuiteFile {
+    /// Scenarios in file order
+    pub scenarios: Vec<Scenario>,
+    /// Where to also write the results as JSON
+    pub json: Option<PathBuf>,
+    /// Where to also write the results as CSV
+    pub csv: Option<PathBuf>,
+}
+
+/// Load a suite file, starting each scenario's policy from `base`
+///
+/// `given` lists the policy keys set on the command line, whose values in
+/// `base` win over the file. Report paths are resolved against the
+/// directory of the file.
+pub fn load(path: &Path, base: &BenchmarkConfig, given: &[&str]) -> Result<SuiteFile, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read suite '{}': {}", path.display(), e))?;
+    let mut file = parse(&text, base, given)
+        .map_err(|e| format!("Invalid suite '{}': {}", path.display(), e))?;
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    file.json = file.json.map(|json| dir.join(json));
+    file.csv = file.csv.map(|csv| dir.join(csv));
+    Ok(file)
+}
+
+/// Parse the text of a suite file
+///
+/// Report paths are returned as written.
+pub fn parse(text: &str, base: &BenchmarkConfig, given: &[&str]) -> Result<SuiteFile, String> {
+    let document: Table = text
+        .parse()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    check_keys(&document, &["defaults", "report", "scenario"], "the file")?;
+
+    let mut defaults = *base;
+    if let Some(table) = document.get("defaults") {
+        let table = as_table(table, "[defaults]")?;
+        check_keys(table, &POLICY_KEYS, "[defaults]")?;
+        apply_policy(table, &mut defaults)?;
+    }
+
+    let (mut json, mut csv) = (None, None);
+    if let Some(table) = document.get("report") {
+        let table = as_table(table, "[report]")?;
+        check_keys(table, &["json", "csv"], "[report]")?;
+        json = table.get("json").map(|v| path(v, "json")).transpose()?;
+        csv = table.get("csv").map(|v| path(v, "csv")).transpose()?;
+    }
+
+    let scenarios = match document.get("scenario") {
+        Some(Value::Array(tables)) => tables
+            .iter()
+            .enumerate()
+            .map(|(i, table)| {
+                let table = as_table(table, "[[scenario]]")?;
+                let mut scenario = parse_scenario(table, &defaults)
+                    .map_err(|e| format!("scenario {}: {}", i + 1, e))?;
+                keep_given(&mut scenario.config, base, given);
+                Ok(scenario)
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        Some(_) => return Err("'scenario' must be an array of tables ([[scenario]])".to_string()),
+        None => Vec::new(),
+    };
+    if scenarios.is_empty() {
+        return Err("no [[scenario]] to run".to_string());
+    }
+
+    Ok(SuiteFile {
+        scenarios,
+        json,
+        csv,
+    })
+}
+
+/// Read one `[[scenario]]` table
+fn parse_scenario(table: &Table, defaults: &BenchmarkConfig) -> Result<Scenario, String> {
+    let allowed: Vec<&str> = SCENARIO_KEYS.iter().chain(&POLICY_KEYS).copied().collect();
+    check_keys(table, &allowed, "a scenario")?;
+
+    let text = |key: &str| -> Result<String, String> {
+        match table.get(key) {
+            Some(Value::String(s)) if !s.is_empty() => Ok(s.clone()),
+            Some(_) => Err(format!("'{}' must be a non-empty string", key)),
+            None => Err(format!("missing '{}'", key)),
+        }
+    };
+    let operation = text("operation")?;
+    let name = match table.get("name") {
+        Some(_) => text("name")?,
+        None => operation.clone(),
+    };
+
+    let inputs = match table.get("inputs") {
+        Some(Value::Array(inputs)) if !inputs.is_empty() => inputs
+            .iter()
+            .map(|input| match input {
+                Value::String(s) => Ok(vec![s.clone()]),
+                Value::Array(_) => strings(input, "each input"),
+                _ => Err("each input must be a string or an array of strings".to_string()),
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        Some(_) => return Err("'inputs' must be a non-empty array".to_string()),
+        None => return Err("missing 'inputs'".to_string()),
+    };
+    let options = match table.get("options") {
+        Some(value) => strings(value, "'options'")?,
+        None => Vec::new(),
+    };
+
+    let mut config = *defaults;
+    apply_policy(table, &mut config).map_err(|e| format!("'{}': {}", name, e))?;
+
+    Ok(Scenario {
+        name,
+        operation,
+        inputs,
+        options,
+        config,
+    })
+}
+
+/// Set the policy keys present in `table`
+fn apply_policy(table: &Table, config: &mut BenchmarkConfig) -> Result<(), String> {
+    let duration = |key: &str| -> Result<Option<std::time::Duration>, String> {
+        match table.get(key) {
+            Some(Value::String(s)) => parse_duration(s).map(Some),
+            Some(_) => Err(format!("'{}' must be a duration such as \"2s\"", key)),
+            None => Ok(None),
+        }
+    };
+    if let Some(time) = duration("time")? {
+        config.measurement_time = time;
+    }
+    if let Some(warm_up) = duration("warm-up")? {
+        config.warm_up = warm_up;
+    }
+
+    match table.get("min-samples") {
+        Some(Value::Integer(n)) if *n > 0 => config.min_samples = *n as usize,
+        Some(_) => return Err("'min-samples' must be a positive integer".to_string()),
+        None => {}
+    }
+    match table.get("memory") {
+        Some(Value::Boolean(memory)) => config.track_memory = *memory,
+        Some(_) => return Err("'memory' must be true or false".to_string()),
+        None => {}
+    }
+    Ok(())
+}
+
+/// Restore the policy keys in `given` to their values in `base`
+fn keep_given(config: &mut BenchmarkConfig, base: &BenchmarkConfig, given: &[&str]) {
+    for &key in given {
+        match key {
+            "time" => config.measurement_time = base.measurement_time,
+            "warm-up" => config.warm_up = base.warm_up,
+            "min-samples" => config.min_samples = base.min_samples,
+            "memory" => config.track_memory = base.track_memory,
+            _ => {}
+        }
+    }
+}
+
+/// Fail on the first key of `table` that is not `allowed`
+fn check_keys(table: &Table, allowed: &[&str], place: &str) -> Result<(), String> {
+    match table.keys().find(|key| !allowed.contains(&key.as_str())) {
+        Some(key) => Err(format!("unknown key '{}' in {}", key, place)),
+        None => Ok(()),
+    }
+}
+
+/// `value` as a table
+fn as_table<'a>(value: &'a Value, place: &str) -> Result<&'a Table, String> {
+    value
+        .as_table()
+        .ok_or_else(|| format!("{} must be a table", place))
+}
+
+/// `value` as an array of strings
+fn strings(value: &Value, what: &str) -> Result<Vec<String>, String> {
+    value
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .ok_or_else(|| format!("{} must be an array of strings", what))
+}
+
+/// `value` as a path
+fn path(value: &Value, key: &str) -> Result<PathBuf, String> {
+    match value.as_str() {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Err(format!("'{}' must be a file path", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const SUITE: &str = r#"
+        [defaults]
+        time = "1s"
+        min-samples = 20
+
+        [report]
+        json = "out/bench.json"
+
+        [[scenario]]
+        name = "anagrams"
+        operation = "anagram"
+        inputs = ["cat", "test"]
+
+        [[scenario]]
+        operation = "art"
+        inputs = [["RUST", "code", "3"]]
+        options = ["--format", "png"]
+        warm-up = "50ms"
+        memory = true
+    "#;
+
+    #[test]
+    fn test_parse_suite() {
+        let base = BenchmarkConfig::default();
+        let suite = parse(SUITE, &base, &[]).unwrap();
+
+        assert_eq!(suite.json, Some(PathBuf::from("out/bench.json")));
+        assert_eq!(suite.csv, None);
+        assert_eq!(suite.scenarios.len(), 2);
+
+        let anagrams = &suite.scenarios[0];
+        assert_eq!(anagrams.name, "anagrams");
+        assert_eq!(anagrams.runs(), vec![vec!["cat"], vec!["test"]]);
+        assert_eq!(anagrams.config.measurement_time, Duration::from_secs(1));
+        assert_eq!(anagrams.config.min_samples, 20);
+        assert_eq!(anagrams.config.warm_up, base.warm_up);
+
+        let art = &suite.scenarios[1];
+        assert_eq!(art.name, "art", "the operation names unnamed scenarios");
+        assert_eq!(
+            art.runs(),
+            vec![vec!["RUST", "code", "3", "--format", "png"]]
+        );
+        assert_eq!(art.config.warm_up, Duration::from_millis(50));
+        assert_eq!(art.config.measurement_time, Duration::from_secs(1));
+        assert!(art.config.track_memory);
+    }
+
+    #[test]
+    fn test_parse_suite_errors() {
+        let base = BenchmarkConfig::default();
+        let error = |text: &str| parse(text, &base, &[]).unwrap_err();
+
+        assert!(error("[[scenario]]\noperation = \"anagram\"").contains("missing 'inputs'"));
+        assert!(error("").contains("no [[scenario]]"));
+        assert!(error("[defaults]\ntiem = \"1s\"").contains("unknown key 'tiem'"));
+        assert!(error("[[scenario]]\noperation = \"anagram\"\ninputs = [1]").contains("scenario 1"));
+        assert!(
+            error("[[scenario]]\noperation = \"anagram\"\ninputs = [\"a\"]\ntime = \"soon\"")
+                .contains("'anagram'")
+        );
+        assert!(
+            error("[[scenario]]\noperation = \"anagram\"\ninputs = [\"a\"]\nmin-samples = 0")
+                .contains("positive integer")
+        );
+        assert!(!error("not toml =").is_empty());
+        assert!(load(Path::new("no-such-suite.toml"), &base, &[]).is_err());
+    }
+
+    #[test]
+    fn test_command_line_wins() {
+        let base = BenchmarkConfig {
+            measurement_time: Duration::from_millis(10),
+            warm_up: Duration::from_millis(1),
+            ..BenchmarkConfig::default()
+        };
+        let suite = parse(SUITE, &base, &["time", "warm-up"]).unwrap();
+
+        for scenario in &suite.scenarios {
+            assert_eq!(scenario.config.measurement_time, base.measurement_time);
+            assert_eq!(scenario.config.warm_up, base.warm_up);
+            assert_eq!(
+                scenario.config.min_samples, 20,
+                "not given, so the file sets it"
+            );
+        }
+        assert!(suite.scenarios[1].config.track_memory);
+    }
+
+    #[test]
+    fn test_load_resolves_reports() {
+        let dir = std::env::temp_dir().join(format!("augusto-suite-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("benches.toml");
+        std::fs::write(&path, SUITE).unwrap();
+
+        let suite = load(&path, &BenchmarkConfig::default(), &[]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(suite.unwrap().json, Some(dir.join("out/bench.json")));
+    }
+}
@@ -16,18 +16,30 @@
 //! augusto bench anagram "word"
 //! ```
 
-use std::{collections::HashSet, env};
+use std::{
+    collections::HashSet,
+    env,
+    io::{self, Write},
+};
 mod anagram;
 mod ascii_art;
 mod benchmark;
 
+/// Counts allocations for `bench --memory`; plain system allocation otherwise
+#[global_allocator]
+static ALLOCATOR: benchmark::alloc::CountingAllocator = benchmark::alloc::CountingAllocator;
+
 /// Main entry point for the augusto CLI tool
 ///
 /// # Commands
 ///
 /// - `anagram <word>` - Generate all anagrams of a word
 /// - `art <main_word> <filler_word>` - Create ASCII art using one word to fill another
-/// - `bench <operation> <args...>` - Benchmark an operation and show performance stats
+/// - `calligram <shape> <text>` - Write text along a spiral, rings, wave, heart or pyramid
+/// - `read-art [file]` - Recognise the letters spelled by an art block
+/// - `font-editor <file>` - Draw the glyphs of an art font in the terminal
+/// - `shape <image> <filler_word>` - Fill the dark parts of a picture with a word
+/// - `bench [options] [--] <command> <args...>` - Benchmark a command and show performance stats
 ///
 /// # Examples
 ///
@@ -48,71 +60,54 @@ fn main() {
     let command = &args[1].to_lowercase();
 
     match command.as_str() {
-        "anagram" | "ana" => {
-            if args.len() < 3 {
-                eprintln!("Error: Missing word for anagram generation");
-                eprintln!("\nUsage: augusto anagram <word>");
-                eprintln!("Example: augusto anagram \"cat\"");
-                std::process::exit(1);
+        "bench" | "benchmark" | "perf" if args.get(2).is_some_and(|arg| arg == "scaling") => {
+            match parse_scaling_options(&args[3..]) {
+                Ok(options) => run_scaling(&options),
+                Err(message) => {
+                    eprintln!("Error: {}", message);
+                    print_scaling_usage();
+                    std::process::exit(1);
+                }
             }
-            run_anagram(&args[2]);
         }
-        "art" | "ascii" => {
-            if args.len() < 4 {
-                eprintln!("Error: Missing words for ASCII art generation");
-                eprintln!("\nUsage: augusto art <main_word> <filler_word> [spacing]");
-                eprintln!("Example: augusto art \"RUST\" \"code\"");
-                eprintln!("         augusto art \"RUST\" \"code\" 2");
+        "bench" | "benchmark" | "perf" => match parse_bench_command(&args[2..]) {
+            Ok((BenchTarget::Command(positional), options)) => run_benchmark(&positional, &options),
+            Ok((BenchTarget::Suite(path), options)) => run_suite(&path, &options),
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                print_bench_usage();
                 std::process::exit(1);
             }
-            let spacing = if let Some(s) = args.get(4) {
-                match s.parse::<usize>() {
-                    Ok(val) => val,
-                    Err(_) => {
-                        eprintln!("Error: Invalid spacing value '{}'. Spacing must be a non-negative integer.", s);
-                        eprintln!("\nUsage: augusto art <main_word> <filler_word> [spacing]");
-                        eprintln!("Example: augusto art \"RUST\" \"code\"");
-                        eprintln!("         augusto art \"RUST\" \"code\" 2");
-                        std::process::exit(1);
-                    }
-                }
-            } else {
-                0
-            };
-            run_ascii_art(&args[2], &args[3], spacing);
-        }
-        "bench" | "benchmark" | "perf" => {
-            if args.len() < 3 {
-                eprintln!("Error: Missing operation to benchmark");
-                eprintln!("\nUsage: augusto bench <operation> <args...>");
-                eprintln!("Example: augusto bench anagram \"test\"");
-                eprintln!("         augusto bench art \"HI\" \"rust\"");
+        },
+        "compare" | "comp" => match parse_bench_options(&args[2..]) {
+            Ok((words, _)) if words.is_empty() => {
+                eprintln!("Error: Missing words for benchmark comparison");
+                print_compare_usage();
                 std::process::exit(1);
             }
-            run_benchmark(&args[2..]);
-        }
-        "compare" | "comp" => {
-            if args.len() < 3 {
-                eprintln!("Error: Missing words for benchmark comparison");
-                eprintln!("\nUsage: augusto compare <word1> <word2> ...");
-                eprintln!("Example: augusto compare \"cat\" \"test\" \"program\"");
+            Ok((words, options)) => run_comparison(&words, &options),
+            Err(message) => {
+                eprintln!("Error: {}", message);
+                print_compare_usage();
                 std::process::exit(1);
             }
-            run_comparison(&args[2..]);
-        }
+        },
         "help" | "--help" | "-h" => {
             print_usage();
         }
-        _ => {
+        name => match find_command(name) {
+            Some(command) => run_command(command, &args[2..]),
             // For backwards compatibility, if no command is recognized, try as anagram
-            if args.len() == 2 {
-                run_anagram(&args[1]);
-            } else {
+            None if args.len() == 2 => {
+                let anagram = find_command("anagram").expect("anagram is registered");
+                run_command(anagram, &args[1..]);
+            }
+            None => {
                 eprintln!("Error: Unknown command '{}'", command);
                 print_usage();
                 std::process::exit(1);
             }
-        }
+        },
     }
 }
 
@@ -124,9 +119,18 @@ fn print_usage() {
     println!("    augusto <command> [arguments]");
     println!();
     println!("COMMANDS:");
-    println!("    anagram <word>                      Generate all anagrams of a word");
-    println!("    art <main> <filler> [spacing]       Create ASCII art (optional spacing)");
+    for command in &COMMANDS {
+        let usage = format!("{} {}", command.names[0], command.usage);
+        // Long usages put the summary on a line of its own
+        if usage.len() < 36 {
+            println!("    {:<36}{}", usage, command.summary);
+        } else {
+            println!("    {}", usage);
+            println!("    {:<36}{}", "", command.summary);
+        }
+    }
     println!("    bench <operation> <args...>         Benchmark an operation with stats");
+    println!("    bench scaling <operation>           Fit the complexity of an operation");
     println!("    compare <word1> <word2> ...         Compare anagram performance");
     println!("    help                                Show this help message");
     println!();
@@ -134,109 +138,1566 @@ fn print_usage() {
     println!("    augusto anagram \"cat\"");
     println!("    augusto art \"RUST\" \"code\"");
     println!("    augusto art \"RUST\" \"code\" 2");
+    println!("    augusto art \"LUXO\" \"LIXO\" --color rainbow");
+    println!("    augusto calligram spiral \"concrete poetry \"");
+    println!("    augusto art \"LUXO\" \"LIXO\" | augusto read-art");
+    println!("    augusto font-editor my.font");
+    println!("    augusto shape cat.png \"miau\" --width 80 --dither");
     println!("    augusto bench anagram \"test\"");
     println!("    augusto bench art \"HI\" \"rust\"");
+    println!("    augusto bench -- art \"RUST\" \"code\" 3 --color rainbow");
+    println!("    augusto bench scaling anagram --from 3 --to 8");
+    println!("    augusto bench --suite benches.toml");
     println!("    augusto compare \"cat\" \"test\" \"program\"");
     println!();
     println!("For backwards compatibility, you can also use:");
     println!("    augusto <word>                  (same as 'anagram' command)");
 }
 
-/// Run anagram generation
-fn run_anagram(input: &str) {
-    // Validate input
-    if input.is_empty() {
-        eprintln!("Error: Input word cannot be empty");
+/// Output of one run of a command
+enum Output {
+    /// Every anagram, repeats included
+    Words(Vec<String>),
+    /// Plain text, such as a reading
+    Text(String),
+    /// Rendered art
+    Bytes(Vec<u8>),
+}
+
+impl Output {
+    /// Size reported by benchmarks: items for anagrams, characters for
+    /// text and bytes for rendered art
+    fn size(&self) -> usize {
+        match self {
+            Output::Words(words) => words.len(),
+            Output::Text(text) => text.chars().count(),
+            Output::Bytes(bytes) => bytes.len(),
+        }
+    }
+
+    /// What the command writes
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            Output::Words(words) => {
+                // Remove duplicates by collecting into a HashSet
+                let unique: HashSet<String> = words.into_iter().collect();
+                format!("{:?}\n", unique).into_bytes()
+            }
+            Output::Text(text) => text.into_bytes(),
+            Output::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Work of a command: produces its output without writing it
+type Work = Box<dyn FnMut() -> Result<Output, String>>;
+
+/// A command whose arguments have been parsed
+enum Prepared {
+    /// Produces output that is written to a file, or to stdout
+    Output { work: Work, path: Option<String> },
+    /// Runs in the terminal, so it cannot be timed
+    Interactive {
+        run: Box<dyn FnOnce() -> Result<(), String>>,
+        /// Why it cannot be benchmarked
+        reason: &'static str,
+    },
+}
+
+impl Prepared {
+    /// Output written to `path`, or to stdout when it is `None`
+    fn output(
+        path: Option<String>,
+        work: impl FnMut() -> Result<Output, String> + 'static,
+    ) -> Self {
+        Prepared::Output {
+            work: Box::new(work),
+            path,
+        }
+    }
+
+    /// The work to time, or why there is none
+    fn into_work(self) -> Result<Work, String> {
+        match self {
+            Prepared::Output { work, .. } => Ok(work),
+            Prepared::Interactive { reason, .. } => Err(reason.to_string()),
+        }
+    }
+}
+
+/// A command run as `augusto <name> <args...>`
+struct Command {
+    /// Names accepted on the command line, the first one being shown in help
+    names: &'static [&'static str],
+    /// Arguments shown in help
+    usage: &'static str,
+    /// One-line description shown in help
+    summary: &'static str,
+    /// Operation name in reports and baselines, or `None` for commands that
+    /// only run interactively
+    operation: Option<&'static str>,
+    /// Parse the command's arguments
+    ///
+    /// Inputs such as files are read on the first run of the work, so that
+    /// benchmarks time the command without its input.
+    prepare: fn(&[String]) -> Result<Prepared, String>,
+    /// Display the command's usage after an argument error
+    print_usage: fn(),
+    /// How `bench scaling` measures the command, if it can
+    scaling: Option<Scaling>,
+}
+
+/// How `bench scaling` measures a command
+struct Scaling {
+    /// Arguments for an input of `n` characters
+    args: fn(usize) -> Vec<String>,
+    /// Longest input the command can handle in reasonable time and memory
+    max_length: Option<usize>,
+}
+
+/// Every command besides `bench`, `compare` and `help`
+///
+/// Registering a command here makes `main` run it and `bench` time it: both
+/// go through its `prepare` function and the work it returns.
+const COMMANDS: [Command; 6] = [
+    Command {
+        names: &["anagram", "ana"],
+        usage: "<word>",
+        summary: "Generate all anagrams of a word",
+        operation: Some("Anagram Generation"),
+        prepare: |args| {
+            let word = match args {
+                [word] if !word.is_empty() => word.clone(),
+                [] => return Err("Missing word for anagram generation".to_string()),
+                [_] => return Err("Input word cannot be empty".to_string()),
+                [_, extra, ..] => return Err(format!("Unexpected argument '{}'", extra)),
+            };
+            Ok(Prepared::output(None, move || {
+                Ok(Output::Words(anagram::letter_combinations(&word)))
+            }))
+        },
+        print_usage: print_anagram_usage,
+        scaling: Some(Scaling {
+            args: |n| vec![benchmark::scaling::input_of_length(n, false)],
+            // Every anagram is kept in memory; 10! of them take hundreds of megabytes
+            max_length: Some(10),
+        }),
+    },
+    Command {
+        names: &["art", "ascii"],
+        usage: "<main_word> <filler_word> [spacing] [art options]",
+        summary: "Create ASCII art of a word filled with another",
+        operation: Some("ASCII Art Generation"),
+        prepare: |args| {
+            let options = parse_art_options(args)?;
+            if let Some(animation) = options.animation {
+                if options.render.format != ArtFormat::Gif {
+                    return Ok(Prepared::Interactive {
+                        run: Box::new(move || play_animation(&options, animation)),
+                        reason: "Terminal animations can only be played; use --format gif",
+                    });
+                }
+            }
+            let color = output_color(&options.render);
+            Ok(Prepared::output(options.render.output.clone(), move || {
+                art_output(&options, color).map(Output::Bytes)
+            }))
+        },
+        print_usage: print_art_usage,
+        scaling: Some(Scaling {
+            args: |n| {
+                vec![
+                    benchmark::scaling::input_of_length(n, true),
+                    "code".to_string(),
+                ]
+            },
+            max_length: None,
+        }),
+    },
+    Command {
+        names: &["calligram"],
+        usage: "<shape> <text> [calligram options]",
+        summary: "Spiral, circle, wave, heart or triangle",
+        operation: Some("Calligram Generation"),
+        prepare: |args| {
+            let options = parse_calligram_options(args)?;
+            let color = output_color(&options.render);
+            Ok(Prepared::output(options.render.output.clone(), move || {
+                calligram_output(&options, color).map(Output::Bytes)
+            }))
+        },
+        print_usage: print_calligram_usage,
+        scaling: None,
+    },
+    Command {
+        names: &["read-art", "read"],
+        usage: "[file] [read-art options]",
+        summary: "Read the word spelled by an art block",
+        operation: Some("Art Recognition"),
+        prepare: |args| {
+            let options = parse_read_options(args)?;
+            let mut input = None;
+            Ok(Prepared::output(None, move || {
+                let text = match &input {
+                    Some(text) => text,
+                    None => input.insert(read_art_input(&options)?),
+                };
+                read_art_output(text, &options).map(Output::Text)
+            }))
+        },
+        print_usage: print_read_usage,
+        scaling: None,
+    },
+    Command {
+        names: &["font-editor", "fonts"],
+        usage: "<file> [--filler <word>]",
+        summary: "Edit the glyphs of an art font",
+        operation: None,
+        prepare: |args| {
+            let options = parse_font_editor_options(args)?;
+            Ok(Prepared::Interactive {
+                run: Box::new(move || run_font_editor(&options)),
+                reason: "The font editor is interactive",
+            })
+        },
+        print_usage: print_font_editor_usage,
+        scaling: None,
+    },
+    Command {
+        names: &["shape"],
+        usage: "<image> <filler_word> [shape options]",
+        summary: "Fill a PGM/PNG picture with a word",
+        operation: Some("Shape Filling"),
+        prepare: |args| {
+            let options = parse_shape_options(args)?;
+            let color = output_color(&options.render);
+            let mut image = None;
+            Ok(Prepared::output(options.render.output.clone(), move || {
+                let image = match &image {
+                    Some(image) => image,
+                    None => image.insert(ascii_art::shape::load_image(&options.image)?),
+                };
+                shape_output(image, &options, color).map(Output::Bytes)
+            }))
+        },
+        print_usage: print_shape_usage,
+        scaling: None,
+    },
+];
+
+/// The command called `name`, if any
+fn find_command(name: &str) -> Option<&'static Command> {
+    let name = name.to_lowercase();
+    COMMANDS
+        .iter()
+        .find(|command| command.names.contains(&name.as_str()))
+}
+
+/// Run a command and write its output
+fn run_command(command: &Command, args: &[String]) {
+    let prepared = (command.prepare)(args).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        (command.print_usage)();
         std::process::exit(1);
+    });
+
+    let result = match prepared {
+        Prepared::Output { mut work, path } => {
+            work().map(|output| write_output(&output.into_bytes(), path.as_deref()))
+        }
+        Prepared::Interactive { run, .. } => run(),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Display usage information for the `anagram` command
+fn print_anagram_usage() {
+    eprintln!("\nUsage: augusto anagram <word>");
+    eprintln!("Example: augusto anagram \"cat\"");
+}
+
+/// Output formats supported by the `art` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArtFormat {
+    Text,
+    Svg,
+    Html,
+    Png,
+    Gif,
+}
+
+impl ArtFormat {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Ok(ArtFormat::Text),
+            "svg" => Ok(ArtFormat::Svg),
+            "html" => Ok(ArtFormat::Html),
+            "png" => Ok(ArtFormat::Png),
+            "gif" => Ok(ArtFormat::Gif),
+            _ => Err(format!("Unknown output format '{}'", name)),
+        }
     }
+}
+
+/// Output options shared by every command that produces word art
+struct RenderOptions {
+    color: ascii_art::color::ColorMode,
+    format: ArtFormat,
+    output: Option<String>,
+    svg: ascii_art::svg::SvgOptions,
+    html: ascii_art::html::HtmlOptions,
+    raster: ascii_art::raster::RasterOptions,
+    gif: ascii_art::raster::GifOptions,
+    title: Option<String>,
+}
 
-    // Generate anagrams
-    let result: Vec<String> = anagram::letter_combinations(input);
+impl RenderOptions {
+    fn new() -> Self {
+        Self {
+            color: ascii_art::color::ColorMode::None,
+            format: ArtFormat::Text,
+            output: None,
+            svg: ascii_art::svg::SvgOptions::default(),
+            html: ascii_art::html::HtmlOptions::default(),
+            raster: ascii_art::raster::RasterOptions::default(),
+            gif: ascii_art::raster::GifOptions::default(),
+            title: None,
+        }
+    }
 
-    // Remove duplicates by collecting into a HashSet
-    let unique_anagrams: HashSet<_> = result.into_iter().collect();
+    /// Apply an output flag, returning `Ok(false)` if `name` is not one
+    fn set(&mut self, name: &str, value: &mut FlagValue) -> Result<bool, String> {
+        match name {
+            "color" | "colour" => self.color = ascii_art::color::ColorMode::parse(&value()?)?,
+            "format" => self.format = ArtFormat::parse(&value()?)?,
+            "output" => self.output = Some(value()?),
+            "font-family" => self.svg.font_family = value()?,
+            "font-size" => self.svg.font_size = parse_size(name, &value()?)?,
+            "fg" => self.svg.foreground = value()?,
+            "bg" => {
+                let bg = value()?;
+                self.svg.background = if bg == "none" { None } else { Some(bg) };
+            }
+            "cell-width" => self.svg.cell_width = parse_size(name, &value()?)?,
+            "cell-height" => self.svg.cell_height = parse_size(name, &value()?)?,
+            "html-layout" => self.html.layout = ascii_art::html::HtmlLayout::parse(&value()?)?,
+            "title" => self.title = Some(value()?),
+            "pixel-size" => {
+                self.raster.pixel_size =
+                    parse_count_in(name, &value()?, 1..=ascii_art::raster::MAX_PIXEL_SIZE)?
+            }
+            "padding" => {
+                self.raster.padding =
+                    parse_count_in(name, &value()?, 0..=ascii_art::raster::MAX_PADDING)?
+            }
+            "palette" => self.gif.palette = ascii_art::raster::Palette::parse(&value()?)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Resolve settings that depend on the other arguments
+    fn finish(&mut self, default_title: &str) -> Result<(), String> {
+        self.html.title = self
+            .title
+            .clone()
+            .unwrap_or_else(|| default_title.to_string());
+
+        // Colours are shared with SVG output, but PNG needs them as hex values
+        if matches!(self.format, ArtFormat::Png | ArtFormat::Gif) {
+            self.raster.foreground = ascii_art::canvas::Rgb::parse_hex(&self.svg.foreground)?;
+            self.raster.background = self
+                .svg
+                .background
+                .as_deref()
+                .map(ascii_art::canvas::Rgb::parse_hex)
+                .transpose()?;
+        }
+        Ok(())
+    }
+}
 
-    println!("{:?}", unique_anagrams);
+/// Options accepted by the `art` command
+struct ArtOptions {
+    main_word: String,
+    filler_word: String,
+    style: ascii_art::ArtStyle,
+    render: RenderOptions,
+    animation: Option<ascii_art::animation::Animation>,
+    fps: f64,
+    loops: usize,
 }
 
-/// Run ASCII art generation
-fn run_ascii_art(main_word: &str, filler_word: &str, spacing: usize) {
-    // Validate input
+/// Convert a frame duration in milliseconds to GIF hundredths of a second
+fn gif_delay(milliseconds: f64) -> u16 {
+    (milliseconds / 10.0).round().clamp(1.0, u16::MAX as f64) as u16
+}
+
+/// Display usage information for the `art` command
+fn print_art_usage() {
+    eprintln!("\nUsage: augusto art <main_word> <filler_word> [spacing] [options]");
+    eprintln!("Example: augusto art \"RUST\" \"code\"");
+    eprintln!("         augusto art \"RUST\" \"code\" 2");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --color letter");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --format svg -o poem.svg");
+    eprintln!("         augusto art \"LUXO\" --filler \"LIXO,LUXO\" --strategy words");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --layout diagonal");
+    eprintln!("         augusto art \"LITTLE\" \"type\" --proportional");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --scale 2 --effect outline,shadow");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --animate morph --fps 15");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" --animate scroll --format gif -o luxo.gif");
+}
+
+/// Parse a non-negative integer option
+fn parse_count(name: &str, value: &str) -> Result<usize, String> {
+    value.parse::<usize>().map_err(|_| {
+        format!(
+            "Invalid value '{}' for --{}. Expected a non-negative integer.",
+            value, name
+        )
+    })
+}
+
+/// Parse a strictly positive integer option
+fn parse_positive_count(name: &str, value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!(
+            "Invalid value '{}' for --{}. Expected a positive number.",
+            value, name
+        )),
+    }
+}
+
+/// Parse an integer option that must lie within `range`
+fn parse_count_in(
+    name: &str,
+    value: &str,
+    range: std::ops::RangeInclusive<usize>,
+) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if range.contains(&count) => Ok(count),
+        _ => Err(format!(
+            "Invalid value '{}' for --{}. Expected {} to {}.",
+            value,
+            name,
+            range.start(),
+            range.end()
+        )),
+    }
+}
+
+/// Largest distance, in cells, a `--shadow-offset` may move the shadow
+const MAX_OFFSET: usize = 64;
+
+/// Parse a `dx,dy` offset such as `2,1` or `-1,1`
+fn parse_offset(value: &str) -> Result<(isize, isize), String> {
+    let invalid = || {
+        format!(
+            "Invalid offset '{}'. Expected two integers from -{} to {} like 2,1.",
+            value, MAX_OFFSET, MAX_OFFSET
+        )
+    };
+    let (x, y) = value.split_once(',').ok_or_else(invalid)?;
+    let x = x.trim().parse::<isize>().map_err(|_| invalid())?;
+    let y = y.trim().parse::<isize>().map_err(|_| invalid())?;
+    if x.unsigned_abs() > MAX_OFFSET || y.unsigned_abs() > MAX_OFFSET {
+        return Err(invalid());
+    }
+    Ok((x, y))
+}
+
+/// Parse a strictly positive number for a size option
+fn parse_size(name: &str, value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(size) if size > 0.0 && size.is_finite() => Ok(size),
+        _ => Err(format!(
+            "Invalid value '{}' for --{}. Expected a positive number.",
+            value, name
+        )),
+    }
+}
+
+/// Parse a number option that must lie within `min..=max`
+fn parse_size_in(name: &str, value: &str, min: f64, max: f64) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(size) if (min..=max).contains(&size) => Ok(size),
+        _ => Err(format!(
+            "Invalid value '{}' for --{}. Expected {} to {}.",
+            value, name, min, max
+        )),
+    }
+}
+
+/// Lazily fetches the value of a flag, so boolean flags consume nothing
+type FlagValue<'a> = dyn FnMut() -> Result<String, String> + 'a;
+
+/// Split arguments into positional words and flags
+///
+/// Flags accept both `--flag value` and `--flag=value` forms, and `-o` is
+/// short for `--output`. Each flag is passed to `on_flag` with its name and
+/// a closure that fetches its value; the positional words are returned.
+/// Giving `--flag=value` to a boolean flag, which never fetches its value,
+/// is an error rather than a silent `true`.
+fn parse_flags(
+    args: &[String],
+    mut on_flag: impl FnMut(&str, &mut FlagValue) -> Result<(), String>,
+) -> Result<Vec<String>, String> {
+    let mut positional = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let flag = match arg.strip_prefix("--") {
+            Some(flag) => flag,
+            None if arg == "-o" => "output",
+            None => {
+                positional.push(arg.clone());
+                continue;
+            }
+        };
+
+        let (name, inline_value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let mut fetched = false;
+        let mut value = || {
+            fetched = true;
+            inline_value
+                .clone()
+                .or_else(|| iter.next().cloned())
+                .ok_or_else(|| format!("Missing value for --{}", name))
+        };
+        on_flag(name, &mut value)?;
+        if inline_value.is_some() && !fetched {
+            return Err(format!("Option '--{}' does not take a value", name));
+        }
+    }
+
+    Ok(positional)
+}
+
+/// Smallest `art --scale`
+const MIN_SCALE: f64 = 0.1;
+
+/// Largest `art --scale`; the canvas grows with its square
+const MAX_SCALE: f64 = 20.0;
+
+/// Parse the arguments following `art` into positional words and flags
+fn parse_art_options(args: &[String]) -> Result<ArtOptions, String> {
+    let mut render = RenderOptions::new();
+    let mut filler = None;
+    let mut strategy = None;
+    let mut style = ascii_art::ArtStyle::default();
+    let mut shadow_offset = None;
+    let mut animation = None;
+    let mut fps = 12.0;
+    let mut loops = 1;
+    let mut frame_delay = None;
+
+    let positional = parse_flags(args, |name, value| {
+        if render.set(name, value)? {
+            return Ok(());
+        }
+        match name {
+            "filler" => filler = Some(value()?),
+            "strategy" => strategy = Some(ascii_art::fill::FillStrategy::parse(&value()?)?),
+            "negative" => style.effects.push(ascii_art::effects::Effect::Inverse),
+            "effect" | "effects" => {
+                for name in value()?.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    style.effects.push(ascii_art::effects::Effect::parse(name)?);
+                }
+            }
+            "shadow-offset" => shadow_offset = Some(parse_offset(&value()?)?),
+            "shadow-filler" => {
+                style.shadow_filler = value()?;
+                if style.shadow_filler.is_empty() {
+                    return Err("Shadow filler cannot be empty".to_string());
+                }
+            }
+            "scale" => style.scale = parse_size_in(name, &value()?, MIN_SCALE, MAX_SCALE)?,
+            "scale-mode" => style.scale_mode = ascii_art::scale::ScaleMode::parse(&value()?)?,
+            "layout" => {
+                let (layout, transforms) = ascii_art::layout::parse_layout(&value()?)?;
+                style.layout = layout;
+                style.transforms = transforms;
+            }
+            "font" => style.font = ascii_art::font::Font::load(&value()?)?,
+            "proportional" => style.proportional = true,
+            "animate" => animation = Some(ascii_art::animation::Animation::parse(&value()?)?),
+            "fps" => fps = parse_size(name, &value()?)?,
+            "loops" => loops = parse_count(name, &value()?)?,
+            "frame-delay" => frame_delay = Some(parse_size(name, &value()?)?),
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    if let Some((x, y)) = shadow_offset {
+        for effect in &mut style.effects {
+            if let ascii_art::effects::Effect::Shadow { dx, dy } = effect {
+                (*dx, *dy) = (x, y);
+            }
+        }
+    }
+
+    // The filler may come from --filler, otherwise it is the second word
+    let mut positional = positional.into_iter();
+    let missing = || "Missing words for ASCII art generation".to_string();
+    let main_word = positional.next().ok_or_else(missing)?;
+    let filler_word = match filler {
+        // A comma-separated --filler lists one word per letter
+        Some(filler) if strategy.is_none() && filler.contains(',') => {
+            style.strategy = ascii_art::fill::FillStrategy::WordPerLetter;
+            filler
+        }
+        Some(filler) => filler,
+        None => positional.next().ok_or_else(missing)?,
+    };
+    if let Some(strategy) = strategy {
+        style.strategy = strategy;
+    }
     if main_word.is_empty() {
-        eprintln!("Error: Main word cannot be empty");
-        std::process::exit(1);
+        return Err("Main word cannot be empty".to_string());
     }
     if filler_word.is_empty() {
-        eprintln!("Error: Filler word cannot be empty");
-        std::process::exit(1);
+        return Err("Filler word cannot be empty".to_string());
     }
 
-    // Generate ASCII art; use word_art for default spacing (0 or 1)
-    let art = if spacing == 0 || spacing == 1 {
-        ascii_art::word_art(main_word, filler_word)
+    if let Some(s) = positional.next() {
+        let spacing = s.parse::<usize>().map_err(|_| {
+            format!(
+                "Invalid spacing value '{}'. Spacing must be a non-negative integer.",
+                s
+            )
+        })?;
+        // Spacing of 0 or 1 both mean the default single-column gap
+        style.spacing = spacing.max(1);
+    }
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    let plays_in_terminal = render.format == ArtFormat::Text && render.output.is_none();
+    if animation.is_some() && !plays_in_terminal && render.format != ArtFormat::Gif {
+        return Err("--animate plays in the terminal or exports with --format gif".to_string());
+    }
+    render.gif.delay = gif_delay(frame_delay.unwrap_or(1000.0 / fps));
+    render.gif.loops = loops;
+
+    render.finish(&main_word)?;
+
+    Ok(ArtOptions {
+        main_word,
+        filler_word,
+        style,
+        render,
+        animation,
+        fps,
+        loops,
+    })
+}
+
+/// Render the art, or its animation as a GIF, without writing it
+///
+/// Terminal animations are played by [`play_animation`] instead.
+fn art_output(options: &ArtOptions, color: ascii_art::color::ColorMode) -> Result<Vec<u8>, String> {
+    match options.animation {
+        Some(animation) => {
+            let frames = ascii_art::animation::frames(
+                &options.main_word,
+                &options.filler_word,
+                &options.style,
+                animation,
+            );
+            let images: Vec<_> = frames
+                .into_iter()
+                .map(|mut frame| {
+                    ascii_art::color::apply(&mut frame, color);
+                    ascii_art::raster::rasterize(&frame, &options.render.raster)
+                })
+                .collect::<Result<_, _>>()?;
+            ascii_art::raster::encode_gif(&images, &options.render.gif)
+        }
+        None => {
+            let canvas = ascii_art::word_art_styled(
+                &options.main_word,
+                &options.filler_word,
+                &options.style,
+            );
+            render_canvas(canvas, &options.render, color)
+        }
+    }
+}
+
+/// Play an animation of the art in the terminal
+fn play_animation(
+    options: &ArtOptions,
+    animation: ascii_art::animation::Animation,
+) -> Result<(), String> {
+    let mut frames = ascii_art::animation::frames(
+        &options.main_word,
+        &options.filler_word,
+        &options.style,
+        animation,
+    );
+
+    if ascii_art::color::stdout_supports_color() {
+        for frame in &mut frames {
+            ascii_art::color::apply(frame, options.render.color);
+        }
+    }
+
+    ascii_art::animation::play(&frames, options.fps, options.loops)
+        .map_err(|e| format!("Could not play animation: {}", e))
+}
+
+/// Options accepted by the `read-art` command
+struct ReadOptions {
+    input: Option<String>,
+    min_confidence: f64,
+    font: ascii_art::font::Font,
+}
+
+/// Display usage information for the `read-art` command
+fn print_read_usage() {
+    eprintln!("\nUsage: augusto read-art [file] [--min-confidence <percent>] [--font <file>]");
+    eprintln!("Example: augusto read-art poem.txt");
+    eprintln!("         augusto art \"LUXO\" \"LIXO\" | augusto read-art");
+}
+
+/// Parse the arguments following `read-art`
+fn parse_read_options(args: &[String]) -> Result<ReadOptions, String> {
+    let mut min_confidence = 0.8;
+    let mut font = ascii_art::font::Font::builtin();
+
+    let positional = parse_flags(args, |name, value| {
+        match name {
+            "font" => font = ascii_art::font::Font::load(&value()?)?,
+            "min-confidence" => {
+                let percent = value()?;
+                min_confidence = match percent.trim_end_matches('%').parse::<f64>() {
+                    Ok(p) if (0.0..=100.0).contains(&p) => p / 100.0,
+                    _ => {
+                        return Err(format!(
+                            "Invalid value '{}' for --min-confidence. Expected 0 to 100.",
+                            percent
+                        ))
+                    }
+                };
+            }
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    let mut positional = positional.into_iter();
+    // No file, or "-", reads from stdin
+    let input = positional.next().filter(|path| path != "-");
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    Ok(ReadOptions {
+        input,
+        min_confidence,
+        font,
+    })
+}
+
+/// Read the art block from the file given, or from stdin
+fn read_art_input(options: &ReadOptions) -> Result<String, String> {
+    match &options.input {
+        Some(path) => {
+            std::fs::read_to_string(path).map_err(|e| format!("Could not read '{}': {}", path, e))
+        }
+        None => io::read_to_string(io::stdin()).map_err(|e| format!("Could not read stdin: {}", e)),
+    }
+}
+
+/// Recognise the glyphs of an art block and format the reading
+fn read_art_output(text: &str, options: &ReadOptions) -> Result<String, String> {
+    let reading = ascii_art::recognize::read_art(text, &options.font, options.min_confidence);
+    if reading.glyphs().next().is_none() {
+        return Err("No glyphs found in the input".to_string());
+    }
+    Ok(reading.format())
+}
+
+/// Options accepted by the `font-editor` command
+struct FontEditorOptions {
+    path: String,
+    filler_word: String,
+}
+
+/// Display usage information for the `font-editor` command
+fn print_font_editor_usage() {
+    eprintln!("\nUsage: augusto font-editor <file> [--filler <word>]");
+    eprintln!("Example: augusto font-editor my.font");
+    eprintln!("         augusto art \"RUST\" \"code\" --font my.font");
+}
+
+/// Parse the arguments following `font-editor`
+fn parse_font_editor_options(args: &[String]) -> Result<FontEditorOptions, String> {
+    let mut filler_word = "augusto".to_string();
+
+    let positional = parse_flags(args, |name, value| {
+        match name {
+            "filler" => filler_word = value()?,
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    let mut positional = positional.into_iter();
+    let path = positional
+        .next()
+        .ok_or_else(|| "Missing font file to edit".to_string())?;
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+    if filler_word.is_empty() {
+        return Err("Filler word cannot be empty".to_string());
+    }
+
+    Ok(FontEditorOptions { path, filler_word })
+}
+
+/// Open the glyph editor, starting from the built-in font for a new file
+fn run_font_editor(options: &FontEditorOptions) -> Result<(), String> {
+    let font = if std::path::Path::new(&options.path).exists() {
+        ascii_art::font::Font::load(&options.path)?
     } else {
-        ascii_art::word_art_with_spacing(main_word, filler_word, spacing)
+        ascii_art::font::Font::builtin()
+    };
+
+    ascii_art::editor::run(font, &options.path, &options.filler_word).map_err(|e| e.to_string())
+}
+
+/// Options accepted by the `shape` command
+struct ShapeCommand {
+    image: String,
+    filler_word: String,
+    strategy: ascii_art::fill::FillStrategy,
+    shape: ascii_art::shape::ShapeOptions,
+    render: RenderOptions,
+}
+
+/// Display usage information for the `shape` command
+fn print_shape_usage() {
+    eprintln!("\nUsage: augusto shape <image.pgm|png> <filler_word> [options]");
+    eprintln!("Example: augusto shape cat.png \"miau\"");
+    eprintln!("         augusto shape cat.pgm \"miau\" --width 80 --dither");
+}
+
+/// Widest `shape --width`, in characters
+const MAX_SHAPE_WIDTH: usize = 1000;
+
+/// Parse the arguments following `shape`
+fn parse_shape_options(args: &[String]) -> Result<ShapeCommand, String> {
+    let mut render = RenderOptions::new();
+    let mut strategy = ascii_art::fill::FillStrategy::Global;
+    let mut shape = ascii_art::shape::ShapeOptions::default();
+
+    let positional = parse_flags(args, |name, value| {
+        if render.set(name, value)? {
+            return Ok(());
+        }
+        match name {
+            "width" => shape.width = parse_count_in(name, &value()?, 1..=MAX_SHAPE_WIDTH)?,
+            "aspect" => shape.aspect = parse_size_in(name, &value()?, MIN_ASPECT, MAX_ASPECT)?,
+            "threshold" => {
+                let threshold = value()?;
+                shape.threshold = threshold.parse::<u8>().map_err(|_| {
+                    format!(
+                        "Invalid value '{}' for --threshold. Expected 0 to 255.",
+                        threshold
+                    )
+                })?;
+            }
+            "dither" => shape.dither = true,
+            "invert" => shape.invert = true,
+            "strategy" => strategy = ascii_art::fill::FillStrategy::parse(&value()?)?,
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    let mut positional = positional.into_iter();
+    let missing = || "Missing image or filler word".to_string();
+    let image = positional.next().ok_or_else(missing)?;
+    let filler_word = positional.next().ok_or_else(missing)?;
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    if filler_word.is_empty() {
+        return Err("Filler word cannot be empty".to_string());
+    }
+    render.finish(&filler_word)?;
+
+    Ok(ShapeCommand {
+        image,
+        filler_word,
+        strategy,
+        shape,
+        render,
+    })
+}
+
+/// Fill a loaded picture with the filler word and render it
+fn shape_output(
+    image: &ascii_art::shape::GrayImage,
+    options: &ShapeCommand,
+    color: ascii_art::color::ColorMode,
+) -> Result<Vec<u8>, String> {
+    let mask = ascii_art::shape::shape_mask(image, &options.shape);
+    let canvas = ascii_art::fill_mask(&mask, &options.filler_word, options.strategy);
+    render_canvas(canvas, &options.render, color)
+}
+
+/// Options accepted by the `calligram` command
+struct CalligramCommand {
+    shape: ascii_art::calligram::CalligramShape,
+    text: String,
+    size: usize,
+    aspect: f64,
+    strategy: Option<ascii_art::fill::FillStrategy>,
+    render: RenderOptions,
+}
+
+/// Display usage information for the `calligram` command
+fn print_calligram_usage() {
+    eprintln!("\nUsage: augusto calligram <spiral|circle|wave|heart|triangle> <text> [options]");
+    eprintln!("Example: augusto calligram spiral \"concrete poetry \"");
+    eprintln!("         augusto calligram heart \"amor\" --size 16 --color rainbow");
+}
+
+/// Smallest calligram or shape `--aspect`
+const MIN_ASPECT: f64 = 0.1;
+
+/// Largest calligram or shape `--aspect`; calligram curves take more steps
+/// as it grows, just as pictures take more rows as it shrinks
+const MAX_ASPECT: f64 = 10.0;
+
+/// Largest calligram `--size`; the path and canvas grow with its square
+const MAX_SIZE: usize = 200;
+
+/// Parse the arguments following `calligram`
+fn parse_calligram_options(args: &[String]) -> Result<CalligramCommand, String> {
+    let mut render = RenderOptions::new();
+    let mut size = 10;
+    let mut aspect = 2.0;
+    let mut strategy = None;
+
+    let positional = parse_flags(args, |name, value| {
+        if render.set(name, value)? {
+            return Ok(());
+        }
+        match name {
+            "size" => size = parse_count_in(name, &value()?, 1..=MAX_SIZE)?,
+            "aspect" => aspect = parse_size_in(name, &value()?, MIN_ASPECT, MAX_ASPECT)?,
+            "strategy" => strategy = Some(ascii_art::fill::FillStrategy::parse(&value()?)?),
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    let mut positional = positional.into_iter();
+    let missing = || "Missing shape or text for calligram".to_string();
+    let shape =
+        ascii_art::calligram::CalligramShape::parse(&positional.next().ok_or_else(missing)?)?;
+    let text = positional.next().ok_or_else(missing)?;
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    if text.is_empty() {
+        return Err("Text cannot be empty".to_string());
+    }
+    render.finish(&text)?;
+
+    Ok(CalligramCommand {
+        shape,
+        text,
+        size,
+        aspect,
+        strategy,
+        render,
+    })
+}
+
+/// Lay the text out along the shape and render it
+fn calligram_output(
+    options: &CalligramCommand,
+    color: ascii_art::color::ColorMode,
+) -> Result<Vec<u8>, String> {
+    let path = ascii_art::calligram::path(options.shape, options.size, options.aspect);
+
+    // Text follows the shape's path unless another distribution is asked for
+    let canvas = match options.strategy {
+        Some(strategy) => {
+            let mask = ascii_art::calligram::path_mask(&path);
+            ascii_art::fill_mask(&mask, &options.text, strategy)
+        }
+        None => ascii_art::fill_path(&path, &options.text),
     };
-    println!("{}", art);
+    render_canvas(canvas, &options.render, color)
 }
 
-/// Run benchmark for an operation
-fn run_benchmark(args: &[String]) {
-    if args.is_empty() {
-        eprintln!("Error: No operation specified for benchmark");
+/// Colour mode to render with
+///
+/// Terminal colour is dropped automatically when piping or when NO_COLOR is set.
+fn output_color(options: &RenderOptions) -> ascii_art::color::ColorMode {
+    match options.format {
+        ArtFormat::Text
+            if options.output.is_some() || !ascii_art::color::stdout_supports_color() =>
+        {
+            ascii_art::color::ColorMode::None
+        }
+        _ => options.color,
+    }
+}
+
+/// Render a canvas in the requested format
+fn render_canvas(
+    mut canvas: ascii_art::canvas::Canvas,
+    options: &RenderOptions,
+    color: ascii_art::color::ColorMode,
+) -> Result<Vec<u8>, String> {
+    ascii_art::color::apply(&mut canvas, color);
+
+    match options.format {
+        ArtFormat::Text => {
+            let mut text = ascii_art::color::render_ansi(&canvas);
+            if options.output.is_none() {
+                text.push('\n');
+            }
+            Ok(text.into_bytes())
+        }
+        ArtFormat::Svg => Ok(ascii_art::svg::render_svg(&canvas, &options.svg).into_bytes()),
+        ArtFormat::Html => Ok(ascii_art::html::render_html(&canvas, &options.html).into_bytes()),
+        ArtFormat::Png => {
+            ascii_art::raster::encode_png(&ascii_art::raster::rasterize(&canvas, &options.raster)?)
+        }
+        ArtFormat::Gif => {
+            let image = ascii_art::raster::rasterize(&canvas, &options.raster)?;
+            ascii_art::raster::encode_gif(&[image], &options.gif)
+        }
+    }
+}
+
+/// Write rendered bytes to a file, or to stdout when no path is given
+fn write_output(bytes: &[u8], output: Option<&str>) {
+    let result = match output {
+        Some(path) => std::fs::write(path, bytes),
+        None => io::stdout().write_all(bytes),
+    };
+    if let Err(e) = result {
+        let target = output.unwrap_or("stdout");
+        eprintln!("Error: Could not write to '{}': {}", target, e);
         std::process::exit(1);
     }
+}
 
-    let operation = &args[0].to_lowercase();
+/// Options shared by the `bench` and `compare` commands
+struct BenchOptions {
+    config: benchmark::BenchmarkConfig,
+    output_format: benchmark::report::OutputFormat,
+    /// Name and entries of the baseline to compare with
+    baseline: Option<(String, Vec<benchmark::baseline::BaselineEntry>)>,
+    save_baseline: Option<String>,
+    /// Slowdown in percent above which a significant change fails the run
+    threshold: f64,
+    /// Iteration policy flags given, named as the keys of a suite file
+    policy_given: Vec<&'static str>,
+}
 
-    match operation.as_str() {
-        "anagram" | "ana" => {
-            if args.len() < 2 {
-                eprintln!("Error: Missing word for anagram benchmark");
-                eprintln!("\nUsage: augusto bench anagram <word>");
-                std::process::exit(1);
+impl BenchOptions {
+    fn new() -> Self {
+        Self {
+            config: benchmark::BenchmarkConfig::default(),
+            output_format: benchmark::report::OutputFormat::Text,
+            baseline: None,
+            save_baseline: None,
+            threshold: 5.0,
+            policy_given: Vec::new(),
+        }
+    }
+
+    /// Apply a benchmark flag; returns `false` if the flag is not one of them
+    fn set(&mut self, name: &str, value: &mut FlagValue) -> Result<bool, String> {
+        match name {
+            "output-format" => {
+                self.output_format = benchmark::report::OutputFormat::parse(&value()?)?
+            }
+            "baseline" => {
+                let name = value()?;
+                let dir = benchmark::baseline::baseline_dir();
+                let entries = benchmark::baseline::load(&dir, &name)?;
+                self.baseline = Some((name, entries));
+            }
+            "save-baseline" => self.save_baseline = Some(value()?),
+            "threshold" => {
+                let percent = value()?;
+                self.threshold = match percent.trim_end_matches('%').parse::<f64>() {
+                    Ok(p) if p >= 0.0 && p.is_finite() => p,
+                    _ => {
+                        return Err(format!(
+                            "Invalid value '{}' for --threshold. Expected a percentage.",
+                            percent
+                        ))
+                    }
+                };
+            }
+            "time" => {
+                self.config.measurement_time = benchmark::parse_duration(&value()?)?;
+                self.policy_given.push("time");
+            }
+            "warm-up" | "warmup" => {
+                self.config.warm_up = benchmark::parse_duration(&value()?)?;
+                self.policy_given.push("warm-up");
+            }
+            "memory" => {
+                self.config.track_memory = true;
+                self.policy_given.push("memory");
             }
+            "min-samples" => {
+                self.config.min_samples = parse_positive_count(name, &value()?)?;
+                self.policy_given.push("min-samples");
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
 
-            let input = &args[1];
+/// Print benchmark results in the requested output format
+fn print_bench_results(results: &[benchmark::BenchmarkStats], options: &BenchOptions) {
+    use benchmark::report::{to_csv, to_json, Environment, OutputFormat};
 
-            // Benchmark anagram generation
-            let stats = benchmark::benchmark_with_result("Anagram Generation", input, || {
-                anagram::letter_combinations(input)
-            });
+    match options.output_format {
+        OutputFormat::Text => {
+            let width = benchmark::chart::terminal_width();
+            for stats in results {
+                println!("{}", stats);
+                print!("{}", stats.format_histogram(width));
+            }
+        }
+        OutputFormat::Json => print!("{}", to_json(results, &Environment::capture())),
+        OutputFormat::Csv => print!("{}", to_csv(results, &Environment::capture())),
+    }
+}
+
+/// Compare results with the `--baseline` and save them with `--save-baseline`
+///
+/// Exits with status 1 when a significant slowdown exceeds the threshold.
+fn check_baselines(results: &[benchmark::BenchmarkStats], options: &BenchOptions) {
+    use benchmark::baseline;
 
-            println!("{}", stats);
+    let mut regressed = false;
+    if let Some((name, entries)) = &options.baseline {
+        let comparisons = baseline::compare(results, entries);
+        let report = baseline::format_comparisons(name, &comparisons, options.threshold);
+        // Keep machine-readable output on stdout parseable
+        match options.output_format {
+            benchmark::report::OutputFormat::Text => {
+                print!("{}", report);
+                let history = baseline::history(&baseline::baseline_dir());
+                let width = benchmark::chart::terminal_width();
+                print!("{}", baseline::format_trends(results, &history, width));
+            }
+            _ => eprint!("{}", report),
         }
-        "art" | "ascii" => {
-            if args.len() < 3 {
-                eprintln!("Error: Missing words for ASCII art benchmark");
-                eprintln!("\nUsage: augusto bench art <main_word> <filler_word>");
+        regressed = comparisons
+            .iter()
+            .any(|c| c.is_regression(options.threshold));
+    }
+
+    if let Some(name) = &options.save_baseline {
+        let environment = benchmark::report::Environment::capture();
+        match baseline::save(&baseline::baseline_dir(), name, results, &environment) {
+            Ok(path) => eprintln!("Saved baseline '{}' to {}", name, path.display()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
                 std::process::exit(1);
             }
+        }
+    }
+
+    if regressed {
+        std::process::exit(1);
+    }
+}
+
+/// Display usage information for the `bench` command
+fn print_bench_usage() {
+    eprintln!("\nUsage: augusto bench [options] [--] <command> <args...>");
+    eprintln!("Example: augusto bench anagram \"test\"");
+    eprintln!("         augusto bench art \"HI\" \"rust\"");
+    eprintln!("         augusto bench --time 1s -- art RUST code 3 --format png");
+    eprintln!("         augusto bench --suite benches.toml --output-format json");
+    eprintln!("         augusto bench anagram \"program\" --time 3s --min-samples 20");
+    eprintln!("         augusto bench anagram \"test\" --output-format json > bench.json");
+    eprintln!("         augusto bench anagram \"program\" --baseline main --threshold 10");
+    print_bench_options();
+    eprintln!("         --suite <file>             Run the scenarios of a TOML suite file");
+}
+
+/// Display the options shared by `bench` and `compare`
+fn print_bench_options() {
+    eprintln!("Options: --time <duration>          Target measurement time (default 2s)");
+    eprintln!("         --warm-up <duration>       Warm-up before measuring (default 500ms)");
+    eprintln!("         --min-samples <n>          Fewest samples to take (default 10)");
+    eprintln!("         --memory                   Also report memory allocated per run");
+    eprintln!("         --output-format <format>   text (default), json or csv");
+    eprintln!("         --save-baseline <name>     Save the results as a named baseline");
+    eprintln!("         --baseline <name>          Compare with a saved baseline");
+    eprintln!(
+        "         --threshold <percent>      Slowdown that fails a --baseline run (default 5)"
+    );
+}
+
+/// Display usage information for the `compare` command
+fn print_compare_usage() {
+    eprintln!("\nUsage: augusto compare <word1> <word2> ... [options]");
+    eprintln!("Example: augusto compare \"cat\" \"test\" \"program\"");
+    eprintln!("         augusto compare \"cat\" \"test\" --output-format csv");
+    print_bench_options();
+}
+
+/// Split the arguments of `bench` or `compare` into positional words and options
+fn parse_bench_options(args: &[String]) -> Result<(Vec<String>, BenchOptions), String> {
+    parse_bench_flags(args, |_, _| Ok(false))
+}
 
-            let main_word = &args[1];
-            let filler_word = &args[2];
+/// Split benchmark arguments, passing flags that are not benchmark options to `extra`
+///
+/// Everything after a `--` is positional, so that a benchmarked command keeps
+/// its own flags. `extra` returns `false` for flags it does not know either.
+fn parse_bench_flags(
+    args: &[String],
+    mut extra: impl FnMut(&str, &mut FlagValue) -> Result<bool, String>,
+) -> Result<(Vec<String>, BenchOptions), String> {
+    let (own, rest) = match args.iter().position(|arg| arg == "--") {
+        Some(i) => (&args[..i], &args[i + 1..]),
+        None => (args, &[][..]),
+    };
+
+    let mut options = BenchOptions::new();
+    let mut positional = parse_flags(own, |name, value| {
+        if options.set(name, value)? || extra(name, value)? {
+            Ok(())
+        } else {
+            Err(format!("Unknown option '--{}'", name))
+        }
+    })?;
+    positional.extend_from_slice(rest);
+    Ok((positional, options))
+}
+
+/// What the `bench` command runs
+#[derive(Debug, PartialEq)]
+enum BenchTarget {
+    /// A command and its arguments
+    Command(Vec<String>),
+    /// The scenarios of a suite file
+    Suite(String),
+}
 
-            // Benchmark ASCII art generation
-            let stats = benchmark::benchmark_operation(
-                "ASCII Art Generation",
-                &format!("{}+{}", main_word, filler_word),
-                || ascii_art::word_art(main_word, filler_word),
+/// Parse the arguments of `bench`, which also accepts `--suite <file>`
+fn parse_bench_command(args: &[String]) -> Result<(BenchTarget, BenchOptions), String> {
+    let mut suite = None;
+    let (positional, options) = parse_bench_flags(args, |name, value| {
+        if name != "suite" {
+            return Ok(false);
+        }
+        suite = Some(value()?);
+        Ok(true)
+    })?;
+
+    let target = match (suite, positional.is_empty()) {
+        (Some(path), true) => BenchTarget::Suite(path),
+        (Some(_), false) => {
+            return Err("A --suite names its own operations; remove the command".to_string())
+        }
+        (None, true) => return Err("Missing operation to benchmark".to_string()),
+        (None, false) => BenchTarget::Command(positional),
+    };
+    Ok((target, options))
+}
+
+/// The command called `name`, if `bench` can time it
+fn find_bench_command(name: &str) -> Option<&'static Command> {
+    find_command(name).filter(|command| command.operation.is_some())
+}
+
+/// Run benchmark for a command line
+///
+/// `args` is the command and its arguments, as they would follow `augusto`.
+fn run_benchmark(args: &[String], options: &BenchOptions) {
+    let Some(command) = find_bench_command(&args[0]) else {
+        eprintln!("Error: Unknown operation '{}' for benchmark", args[0]);
+        print_bench_operations();
+        std::process::exit(1);
+    };
+
+    let stats = benchmark_command(command, &args[1..], &options.config).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        eprintln!(
+            "\nUsage: augusto bench -- {} {}",
+            command.names[0], command.usage
+        );
+        std::process::exit(1);
+    });
+
+    print_bench_results(std::slice::from_ref(&stats), options);
+    check_baselines(&[stats], options);
+}
+
+/// List the commands `bench` can time
+fn print_bench_operations() {
+    eprintln!("\nSupported operations:");
+    for command in COMMANDS.iter().filter(|c| c.operation.is_some()) {
+        eprintln!("  - {} {}", command.names[0], command.usage);
+    }
+}
+
+/// Benchmark a command with the arguments that follow its name
+fn benchmark_command(
+    command: &Command,
+    args: &[String],
+    config: &benchmark::BenchmarkConfig,
+) -> Result<benchmark::BenchmarkStats, String> {
+    let Some(operation) = command.operation else {
+        return Err(format!("'{}' cannot be benchmarked", command.names[0]));
+    };
+
+    // A first run checks that the command succeeds, reads its inputs and
+    // measures its output
+    let mut work = (command.prepare)(args)?.into_work()?;
+    let output_size = work()?.size();
+
+    let input = args.join(" ");
+    Ok(
+        benchmark::benchmark_operation(operation, &input, config, &mut work)
+            .with_output_size(output_size),
+    )
+}
+
+/// Run every scenario of a suite file and report them as one suite
+fn run_suite(path: &str, options: &BenchOptions) {
+    let fail = |message: String| -> ! {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    };
+    let file = benchmark::suite::load(
+        std::path::Path::new(path),
+        &options.config,
+        &options.policy_given,
+    )
+    .unwrap_or_else(|e| fail(e));
+
+    // Check every operation before spending time measuring
+    let mut planned = Vec::new();
+    for scenario in &file.scenarios {
+        match find_bench_command(&scenario.operation) {
+            Some(command) => planned.push((scenario, command)),
+            None => {
+                eprintln!(
+                    "Error: Unknown operation '{}' in scenario '{}'",
+                    scenario.operation, scenario.name
+                );
+                print_bench_operations();
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut suite = benchmark::BenchmarkSuite::new();
+    for (scenario, command) in planned {
+        for args in scenario.runs() {
+            eprintln!(
+                "Running '{}': {} {}",
+                scenario.name,
+                command.names[0],
+                args.join(" ")
             );
+            match benchmark_command(command, &args, &scenario.config) {
+                Ok(stats) => suite.add(stats),
+                Err(e) => fail(format!("Scenario '{}': {}", scenario.name, e)),
+            }
+        }
+    }
+
+    match options.output_format {
+        benchmark::report::OutputFormat::Text => {
+            println!("{}", suite.format_comparison());
+            print!("{}", suite.format_chart(benchmark::chart::terminal_width()));
+        }
+        _ => print_bench_results(suite.results(), options),
+    }
+
+    // A report that cannot be written fails the run only after the others
+    // and the baselines are handled, so no results are lost
+    let environment = benchmark::report::Environment::capture();
+    let reports = [
+        (&file.json, benchmark::report::to_json as fn(_, _) -> String),
+        (&file.csv, benchmark::report::to_csv),
+    ];
+    let mut write_failed = false;
+    for (target, render) in reports {
+        let Some(target) = target else { continue };
+        match write_report(target, &render(suite.results(), &environment)) {
+            Ok(()) => eprintln!("Wrote results to {}", target.display()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                write_failed = true;
+            }
+        }
+    }
+
+    check_baselines(suite.results(), options);
+    if write_failed {
+        std::process::exit(1);
+    }
+}
+
+/// Write a report file, creating its directory if needed
+fn write_report(path: &std::path::Path, text: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Could not create '{}': {}", dir.display(), e))?;
+    }
+    std::fs::write(path, text).map_err(|e| format!("Could not write '{}': {}", path.display(), e))
+}
+
+/// Options accepted by `bench scaling`
+struct ScalingOptions {
+    command: &'static Command,
+    /// Smallest and largest input length
+    from: usize,
+    to: usize,
+    bench: BenchOptions,
+}
+
+/// Display usage information for `bench scaling`
+fn print_scaling_usage() {
+    let names: Vec<&str> = COMMANDS
+        .iter()
+        .filter(|command| command.scaling.is_some())
+        .map(|command| command.names[0])
+        .collect();
+    eprintln!(
+        "\nUsage: augusto bench scaling <{}> [--from <n>] [--to <n>] [options]",
+        names.join("|")
+    );
+    eprintln!("Example: augusto bench scaling anagram --from 3 --to 8");
+    eprintln!("         augusto bench scaling art --from 1 --to 12 --time 500ms");
+    eprintln!("Inputs of every length from --from (default 3) to --to (default 8) are");
+    eprintln!("measured and fitted to O(n), O(n log n), O(n²) and O(n!·n).");
+    print_bench_options();
+}
+
+/// Parse the arguments following `bench scaling`
+fn parse_scaling_options(args: &[String]) -> Result<ScalingOptions, String> {
+    let mut bench = BenchOptions::new();
+    let mut from = 3;
+    let mut to = 8;
+
+    let positional = parse_flags(args, |name, value| {
+        match name {
+            "from" => from = parse_count(name, &value()?)?,
+            "to" => to = parse_count(name, &value()?)?,
+            _ if bench.set(name, value)? => {}
+            _ => return Err(format!("Unknown option '--{}'", name)),
+        }
+        Ok(())
+    })?;
+
+    let mut positional = positional.into_iter();
+    let name = positional
+        .next()
+        .ok_or_else(|| "Missing operation to scale".to_string())?;
+    let (command, scaling) = match find_bench_command(&name) {
+        Some(
+            command @ Command {
+                scaling: Some(scaling),
+                ..
+            },
+        ) => (command, scaling),
+        _ => return Err(format!("Unknown operation '{}' for scaling", name)),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+    if from == 0 || to < from + 2 {
+        return Err(format!(
+            "Invalid range {}..{}. Lengths start at 1 and at least three are needed.",
+            from, to
+        ));
+    }
+    if let Some(max) = scaling.max_length.filter(|&max| to > max) {
+        return Err(format!(
+            "Invalid value {} for --to. '{}' scales up to length {}.",
+            to, command.names[0], max
+        ));
+    }
+
+    Ok(ScalingOptions {
+        command,
+        from,
+        to,
+        bench,
+    })
+}
 
-            println!("{}", stats);
+/// Measure an operation on inputs of increasing length and fit its complexity
+fn run_scaling(options: &ScalingOptions) {
+    use benchmark::scaling;
+
+    let sizes: Vec<usize> = (options.from..=options.to).collect();
+    let mut suite = benchmark::BenchmarkSuite::new();
+
+    let inputs = options
+        .command
+        .scaling
+        .as_ref()
+        .expect("parse_scaling_options checks the command scales")
+        .args;
+
+    for &n in &sizes {
+        eprintln!("Measuring length {}...", n);
+        match benchmark_command(options.command, &inputs(n), &options.bench.config) {
+            Ok(stats) => suite.add(stats),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
         }
-        _ => {
-            eprintln!("Error: Unknown operation '{}' for benchmark", operation);
-            eprintln!("\nSupported operations:");
-            eprintln!("  - anagram <word>");
-            eprintln!("  - art <main_word> <filler_word>");
-            std::process::exit(1);
+    }
+
+    match options.bench.output_format {
+        benchmark::report::OutputFormat::Text => {
+            print!(
+                "{}",
+                scaling::format_scaling(
+                    &sizes,
+                    suite.results(),
+                    benchmark::chart::terminal_width()
+                )
+            )
         }
+        _ => print_bench_results(suite.results(), &options.bench),
     }
+    check_baselines(suite.results(), &options.bench);
 }
 
 /// Run comparison of multiple anagram operations
-fn run_comparison(words: &[String]) {
+fn run_comparison(words: &[String], options: &BenchOptions) {
     if words.is_empty() {
         eprintln!("Error: No words provided for comparison");
         std::process::exit(1);
@@ -245,19 +1706,28 @@ fn run_comparison(words: &[String]) {
     let mut suite = benchmark::BenchmarkSuite::new();
 
     for word in words {
-        let stats = benchmark::benchmark_with_result("Anagram Generation", word, || {
-            anagram::letter_combinations(word)
-        });
+        let stats =
+            benchmark::benchmark_with_result("Anagram Generation", word, &options.config, || {
+                anagram::letter_combinations(word)
+            });
         suite.add(stats);
     }
 
-    println!("{}", suite.format_comparison());
+    match options.output_format {
+        benchmark::report::OutputFormat::Text => {
+            println!("{}", suite.format_comparison());
+            print!("{}", suite.format_chart(benchmark::chart::terminal_width()));
+        }
+        _ => print_bench_results(suite.results(), options),
+    }
+    check_baselines(suite.results(), options);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashSet;
+    use std::time::Duration;
 
     #[test]
     fn test_anagram_combinations() {
@@ -308,4 +1778,559 @@ mod tests {
             art.contains('r') || art.contains('u') || art.contains('s') || art.contains('t');
         assert!(has_filler);
     }
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_art_options() {
+        let options = parse_art_options(&to_args(&["RUST", "code", "2", "--color", "rainbow"]))
+            .expect("valid options");
+        assert_eq!(options.main_word, "RUST");
+        assert_eq!(options.filler_word, "code");
+        assert_eq!(options.style.spacing, 2);
+        assert_eq!(options.render.color, ascii_art::color::ColorMode::Rainbow);
+
+        let options =
+            parse_art_options(&to_args(&["--color=letter", "HI", "x"])).expect("valid options");
+        assert_eq!(options.style.spacing, 1);
+        assert_eq!(options.render.color, ascii_art::color::ColorMode::Letter);
+        assert_eq!(options.render.format, ArtFormat::Text);
+        assert_eq!(options.render.output, None);
+        assert_eq!(options.render.html.title, "HI");
+        assert!(!options.style.proportional);
+
+        let options =
+            parse_art_options(&to_args(&["HI", "x", "--proportional"])).expect("valid options");
+        assert!(options.style.proportional);
+    }
+
+    #[test]
+    fn test_parse_art_svg_options() {
+        let options = parse_art_options(&to_args(&[
+            "LUXO",
+            "LIXO",
+            "--format",
+            "svg",
+            "-o",
+            "poem.svg",
+            "--font-size",
+            "20",
+            "--bg=none",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.render.format, ArtFormat::Svg);
+        assert_eq!(options.render.output.as_deref(), Some("poem.svg"));
+        assert_eq!(options.render.svg.font_size, 20.0);
+        assert_eq!(options.render.svg.background, None);
+
+        assert!(parse_art_options(&to_args(&["A", "x", "--format", "bmp"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--html-layout", "table"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_art_png_options() {
+        let options = parse_art_options(&to_args(&[
+            "HI",
+            "x",
+            "--format",
+            "png",
+            "--fg",
+            "#ff0000",
+            "--bg",
+            "none",
+            "--pixel-size",
+            "3",
+            "--padding",
+            "0",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.render.format, ArtFormat::Png);
+        assert_eq!(
+            options.render.raster.foreground,
+            ascii_art::canvas::Rgb(255, 0, 0)
+        );
+        assert_eq!(options.render.raster.background, None);
+        assert_eq!(options.render.raster.pixel_size, 3);
+        assert_eq!(options.render.raster.padding, 0);
+
+        // PNG colours must be hex values
+        assert!(
+            parse_art_options(&to_args(&["HI", "x", "--format", "png", "--fg", "red"])).is_err()
+        );
+        assert!(parse_art_options(&to_args(&["A", "x", "--cell-width", "-2"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--pixel-size", "0"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--pixel-size", "1000000"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--padding", "100000000000"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_shape_options() {
+        let options = parse_shape_options(&to_args(&[
+            "cat.png",
+            "miau",
+            "--width=80",
+            "--dither",
+            "--threshold",
+            "100",
+            "-o",
+            "cat.svg",
+            "--format",
+            "svg",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.image, "cat.png");
+        assert_eq!(options.filler_word, "miau");
+        assert_eq!(options.shape.width, 80);
+        assert_eq!(options.shape.threshold, 100);
+        assert!(options.shape.dither);
+        assert!(!options.shape.invert);
+        assert_eq!(options.render.format, ArtFormat::Svg);
+        assert_eq!(options.render.output.as_deref(), Some("cat.svg"));
+
+        assert!(parse_shape_options(&to_args(&["cat.png"])).is_err());
+        assert!(parse_shape_options(&to_args(&["cat.png", "x", "--threshold", "300"])).is_err());
+        assert!(parse_shape_options(&to_args(&["cat.png", "x", "--scale", "2"])).is_err());
+        assert!(parse_shape_options(&to_args(&["cat.png", "x", "--dither=no"])).is_err());
+        assert!(parse_shape_options(&to_args(&["cat.png", "x", "--invert=0"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--proportional=0"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--negative=x"])).is_err());
+        for (flag, value) in [
+            ("--width", "0"),
+            ("--width", "1001"),
+            ("--aspect", "0"),
+            ("--aspect", "1e-300"),
+            ("--aspect", "11"),
+        ] {
+            assert!(
+                parse_shape_options(&to_args(&["cat.png", "x", flag, value])).is_err(),
+                "{} {}",
+                flag,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_calligram_options() {
+        let options = parse_calligram_options(&to_args(&[
+            "Spiral",
+            "concrete poetry",
+            "--size",
+            "12",
+            "--strategy=random:3",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.shape, ascii_art::calligram::CalligramShape::Spiral);
+        assert_eq!(options.text, "concrete poetry");
+        assert_eq!(options.size, 12);
+        assert_eq!(options.aspect, 2.0);
+        assert_eq!(
+            options.strategy,
+            Some(ascii_art::fill::FillStrategy::Random(3))
+        );
+        assert_eq!(options.render.html.title, "concrete poetry");
+
+        assert!(parse_calligram_options(&to_args(&["blob", "text"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["heart"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["heart", "x", "--size", "-1"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["heart", "x", "--size", "0"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["heart", "x", "--size", "201"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["heart", "x", "--size", "200"])).is_ok());
+        assert!(parse_calligram_options(&to_args(&["spiral", "x", "--aspect", "1e8"])).is_err());
+        assert!(parse_calligram_options(&to_args(&["spiral", "x", "--aspect", "0.01"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_animation_options() {
+        let options = parse_art_options(&to_args(&[
+            "LUXO",
+            "LIXO",
+            "--animate",
+            "morph",
+            "--fps=20",
+            "--loops",
+            "0",
+        ]))
+        .expect("valid options");
+        assert_eq!(
+            options.animation,
+            Some(ascii_art::animation::Animation::Morph)
+        );
+        assert_eq!(options.fps, 20.0);
+        assert_eq!(options.loops, 0);
+
+        let options = parse_art_options(&to_args(&["LUXO", "LIXO"])).expect("valid options");
+        assert_eq!(options.animation, None);
+        assert_eq!(options.fps, 12.0);
+        assert_eq!(options.loops, 1);
+
+        assert!(parse_art_options(&to_args(&["A", "b", "--animate", "spin"])).is_err());
+        assert!(
+            parse_art_options(&to_args(&["A", "b", "--animate", "reveal", "-o", "a.txt"])).is_err()
+        );
+        assert!(parse_art_options(&to_args(&["A", "b", "--fps", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_gif_options() {
+        let options = parse_art_options(&to_args(&[
+            "LUXO",
+            "LIXO",
+            "--animate",
+            "scroll",
+            "--format",
+            "gif",
+            "-o",
+            "luxo.gif",
+            "--fps",
+            "20",
+            "--loops",
+            "0",
+            "--palette",
+            "web",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.render.format, ArtFormat::Gif);
+        assert_eq!(options.render.gif.delay, 5);
+        assert_eq!(options.render.gif.loops, 0);
+        assert_eq!(options.render.gif.palette, ascii_art::raster::Palette::Web);
+
+        let options = parse_art_options(&to_args(&[
+            "A",
+            "b",
+            "--animate=morph",
+            "--format=gif",
+            "--frame-delay",
+            "250",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.render.gif.delay, 25);
+        assert_eq!(options.render.gif.loops, 1);
+
+        assert!(parse_art_options(&to_args(&["A", "b", "--palette", "sepia"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_read_options() {
+        let options = parse_read_options(&to_args(&["art.txt", "--min-confidence", "90"]))
+            .expect("valid options");
+        assert_eq!(options.input.as_deref(), Some("art.txt"));
+        assert_eq!(options.min_confidence, 0.9);
+
+        let options = parse_read_options(&to_args(&["-"])).expect("valid options");
+        assert_eq!(options.input, None);
+        assert_eq!(options.min_confidence, 0.8);
+
+        assert!(parse_read_options(&to_args(&["--min-confidence", "120"])).is_err());
+        assert!(parse_read_options(&to_args(&["a.txt", "b.txt"])).is_err());
+        assert!(parse_read_options(&to_args(&["--font", "/nonexistent/x.font"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_font_editor_options() {
+        let options = parse_font_editor_options(&to_args(&["my.font", "--filler", "poesia"]))
+            .expect("valid options");
+        assert_eq!(options.path, "my.font");
+        assert_eq!(options.filler_word, "poesia");
+
+        assert!(parse_font_editor_options(&to_args(&[])).is_err());
+        assert!(parse_font_editor_options(&to_args(&["a.font", "b.font"])).is_err());
+        assert!(parse_font_editor_options(&to_args(&["a.font", "--filler", ""])).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench_options() {
+        let (positional, options) = parse_bench_options(&to_args(&[
+            "anagram",
+            "--time",
+            "3s",
+            "test",
+            "--warm-up=200ms",
+            "--min-samples",
+            "20",
+        ]))
+        .expect("valid options");
+        assert_eq!(positional, vec!["anagram", "test"]);
+        assert_eq!(options.config.measurement_time, Duration::from_secs(3));
+        assert_eq!(options.config.warm_up, Duration::from_millis(200));
+        assert_eq!(options.config.min_samples, 20);
+        assert_eq!(options.output_format, benchmark::report::OutputFormat::Text);
+        assert!(!options.config.track_memory);
+        assert_eq!(options.policy_given, ["time", "warm-up", "min-samples"]);
+
+        let (_, options) =
+            parse_bench_options(&to_args(&["cat", "--output-format", "json", "--memory"]))
+                .expect("valid options");
+        assert_eq!(options.output_format, benchmark::report::OutputFormat::Json);
+        assert!(options.config.track_memory);
+        assert!(parse_bench_options(&to_args(&["cat", "--output-format", "xml"])).is_err());
+        assert!(parse_bench_options(&to_args(&["cat", "--memory=false"])).is_err());
+
+        let (_, options) = parse_bench_options(&to_args(&[
+            "cat",
+            "--save-baseline",
+            "main",
+            "--threshold",
+            "10%",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.save_baseline.as_deref(), Some("main"));
+        assert_eq!(options.threshold, 10.0);
+        assert!(options.baseline.is_none());
+        assert!(parse_bench_options(&to_args(&["cat", "--threshold", "-1"])).is_err());
+        assert!(parse_bench_options(&to_args(&["cat", "--baseline", "no-such-baseline"])).is_err());
+
+        let (_, defaults) = parse_bench_options(&to_args(&["cat"])).expect("valid options");
+        assert_eq!(defaults.config, benchmark::BenchmarkConfig::default());
+
+        assert!(parse_bench_options(&to_args(&["cat", "--time", "soon"])).is_err());
+        assert!(parse_bench_options(&to_args(&["cat", "--min-samples", "0"])).is_err());
+        assert!(parse_bench_options(&to_args(&["cat", "--iterations", "5"])).is_err());
+
+        // Flags after `--` belong to the benchmarked command
+        let (positional, options) = parse_bench_options(&to_args(&[
+            "--time", "1s", "--", "art", "RUST", "code", "3", "--time", "x",
+        ]))
+        .expect("valid options");
+        assert_eq!(
+            positional,
+            to_args(&["art", "RUST", "code", "3", "--time", "x"])
+        );
+        assert_eq!(options.config.measurement_time, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_parse_bench_command() {
+        let (target, _) = parse_bench_command(&to_args(&["anagram", "cat"])).expect("valid");
+        assert_eq!(target, BenchTarget::Command(to_args(&["anagram", "cat"])));
+
+        let (target, options) =
+            parse_bench_command(&to_args(&["--suite", "benches.toml", "--memory"])).expect("valid");
+        assert_eq!(target, BenchTarget::Suite("benches.toml".to_string()));
+        assert!(options.config.track_memory);
+
+        assert!(parse_bench_command(&to_args(&[])).is_err());
+        assert!(parse_bench_command(&to_args(&["--suite", "b.toml", "anagram", "cat"])).is_err());
+        // `compare` does not take suites
+        assert!(parse_bench_options(&to_args(&["--suite", "b.toml"])).is_err());
+    }
+
+    #[test]
+    fn test_commands() {
+        let prepare = |args: &[&str]| {
+            let args = to_args(args);
+            let command = find_command(&args[0]).expect("registered command");
+            (command.prepare)(&args[1..])
+        };
+        let run = |args: &[&str]| {
+            let mut work = prepare(args)?.into_work()?;
+            work().map(|output| output.size())
+        };
+
+        assert_eq!(run(&["anagram", "abc"]), Ok(6));
+        assert!(run(&["ANA"]).is_err());
+        assert!(run(&["anagram", "abc", "extra"]).is_err());
+
+        let plain = run(&["art", "HI", "x"]).unwrap();
+        let spaced = run(&["art", "HI", "x", "3"]).unwrap();
+        assert!(spaced > plain, "spacing widens the art");
+        assert!(run(&["art", "HI", "x", "--format", "svg"]).unwrap() > plain);
+        assert!(run(&["art", "HI", "x", "--animate", "reveal", "--format", "gif"]).is_ok());
+        assert!(run(&["art", "HI", "x", "--animate", "reveal"]).is_err());
+        assert!(run(&["art", "HI", ""]).is_err());
+
+        assert!(run(&["calligram", "spiral", "poetry"]).unwrap() > 0);
+
+        let path = std::env::temp_dir().join(format!("augusto-bench-{}.txt", std::process::id()));
+        std::fs::write(&path, ascii_art::word_art("HI", "x")).unwrap();
+        let reading = run(&["read-art", path.to_str().unwrap()]);
+        std::fs::remove_file(&path).unwrap();
+        assert!(reading.unwrap() > 0);
+        // Inputs are read when the work first runs, after the arguments are checked
+        assert!(prepare(&["read-art", "/nonexistent/art.txt"]).is_ok());
+        assert!(run(&["read-art", "/nonexistent/art.txt"]).is_err());
+
+        assert!(prepare(&["font-editor", "my.font"])
+            .unwrap()
+            .into_work()
+            .is_err());
+        assert!(find_command("font-editor").is_some());
+        assert!(find_bench_command("font-editor").is_none());
+        assert!(find_bench_command("Shape").is_some());
+    }
+
+    #[test]
+    fn test_command_output() {
+        let words = Output::Words(to_args(&["ab", "ab"]));
+        assert_eq!(words.size(), 2);
+        assert_eq!(words.into_bytes(), b"{\"ab\"}\n");
+
+        let text = Output::Text("ação".to_string());
+        assert_eq!(text.size(), 4, "text is measured in characters");
+        assert_eq!(Output::Bytes(vec![0; 3]).size(), 3);
+    }
+
+    #[test]
+    fn test_parse_scaling_options() {
+        let options = parse_scaling_options(&to_args(&["anagram", "--to", "6", "--time", "1s"]))
+            .expect("valid options");
+        assert_eq!(options.command.names[0], "anagram");
+        assert_eq!((options.from, options.to), (3, 6));
+        assert_eq!(
+            options.bench.config.measurement_time,
+            Duration::from_secs(1)
+        );
+
+        let options = parse_scaling_options(&to_args(&["ASCII", "--from=1", "--to=12"]))
+            .expect("valid options");
+        assert_eq!(options.command.names[0], "art");
+        assert_eq!((options.from, options.to), (1, 12));
+
+        assert!(parse_scaling_options(&to_args(&[])).is_err());
+        assert!(parse_scaling_options(&to_args(&["shape"])).is_err());
+        assert!(parse_scaling_options(&to_args(&["anagram", "--from", "0"])).is_err());
+        assert!(parse_scaling_options(&to_args(&["anagram", "--from", "5", "--to", "6"])).is_err());
+        assert!(parse_scaling_options(&to_args(&["anagram", "--step", "2"])).is_err());
+        assert!(parse_scaling_options(&to_args(&["anagram", "--to", "10"])).is_ok());
+        assert!(parse_scaling_options(&to_args(&["anagram", "--to", "13"])).is_err());
+        assert!(parse_scaling_options(&to_args(&["art", "--to", "13"])).is_ok());
+    }
+
+    #[test]
+    fn test_parse_art_options_errors() {
+        assert!(parse_art_options(&to_args(&["RUST"])).is_err());
+        assert!(parse_art_options(&to_args(&["RUST", "code", "x"])).is_err());
+        assert!(parse_art_options(&to_args(&["RUST", "code", "--color"])).is_err());
+        assert!(parse_art_options(&to_args(&["RUST", "code", "--shiny"])).is_err());
+        assert!(parse_art_options(&to_args(&["RUST", "code", "2", "3"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_art_fill_options() {
+        let options = parse_art_options(&to_args(&[
+            "LUXO",
+            "--filler",
+            "LIXO,LUXO",
+            "--strategy",
+            "words",
+            "--negative",
+            "2",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.main_word, "LUXO");
+        assert_eq!(options.filler_word, "LIXO,LUXO");
+        assert_eq!(options.style.spacing, 2);
+        assert_eq!(
+            options.style.strategy,
+            ascii_art::fill::FillStrategy::WordPerLetter
+        );
+        assert_eq!(
+            options.style.effects,
+            vec![ascii_art::effects::Effect::Inverse]
+        );
+
+        let options =
+            parse_art_options(&to_args(&["LUXO", "--filler", "LIXO,LUXO"])).expect("valid options");
+        assert_eq!(
+            options.style.strategy,
+            ascii_art::fill::FillStrategy::WordPerLetter
+        );
+        let options = parse_art_options(&to_args(&[
+            "LUXO",
+            "--filler",
+            "LIXO,LUXO",
+            "--strategy",
+            "letter",
+        ]))
+        .expect("valid options");
+        assert_eq!(
+            options.style.strategy,
+            ascii_art::fill::FillStrategy::PerLetter
+        );
+        let options = parse_art_options(&to_args(&["LUXO", "a,b"])).expect("valid options");
+        assert_eq!(
+            options.style.strategy,
+            ascii_art::fill::FillStrategy::Global
+        );
+
+        assert!(parse_art_options(&to_args(&["A", "x", "--strategy", "zigzag"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_art_scale_options() {
+        let options = parse_art_options(&to_args(&[
+            "RUST",
+            "code",
+            "--scale",
+            "2.5",
+            "--scale-mode",
+            "smooth",
+        ]))
+        .expect("valid options");
+        assert_eq!(options.style.scale, 2.5);
+        assert_eq!(
+            options.style.scale_mode,
+            ascii_art::scale::ScaleMode::Smooth
+        );
+
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale", "0"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale", "21"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale", "1e300"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale", "0.05"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale", "20"])).is_ok());
+        assert!(parse_art_options(&to_args(&["A", "x", "--scale-mode", "lanczos"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_art_layout_options() {
+        let options =
+            parse_art_options(&to_args(&["LUXO", "LIXO", "--layout", "vertical,mirror-h"]))
+                .expect("valid options");
+        assert_eq!(options.style.layout, ascii_art::layout::Layout::Vertical);
+        assert_eq!(
+            options.style.transforms,
+            vec![ascii_art::layout::Transform::MirrorHorizontal]
+        );
+
+        assert!(parse_art_options(&to_args(&["A", "x", "--layout", "circle"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_art_effect_options() {
+        let options = parse_art_options(&to_args(&[
+            "RUST",
+            "code",
+            "--effect",
+            "outline,shadow",
+            "--effect=bold",
+            "--shadow-offset",
+            "2,-1",
+            "--shadow-filler",
+            "*",
+        ]))
+        .expect("valid options");
+        assert_eq!(
+            options.style.effects,
+            vec![
+                ascii_art::effects::Effect::Outline,
+                ascii_art::effects::Effect::Shadow { dx: 2, dy: -1 },
+                ascii_art::effects::Effect::Bold,
+            ]
+        );
+        assert_eq!(options.style.shadow_filler, "*");
+
+        assert!(parse_art_options(&to_args(&["A", "x", "--effect", "glow"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--shadow-offset", "2"])).is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--shadow-offset", "65,1"])).is_err());
+        assert!(parse_art_options(&to_args(&[
+            "A",
+            "x",
+            "--shadow-offset",
+            "1,-9223372036854775808"
+        ]))
+        .is_err());
+        assert!(parse_art_options(&to_args(&["A", "x", "--shadow-offset", "-64,64"])).is_ok());
+    }
 }